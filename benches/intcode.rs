@@ -0,0 +1,40 @@
+//! Intcode VM throughput on the day 9 BOOST program, which exercises every
+//! opcode and parameter mode.
+
+use aoc::input::{Source, DEFAULT_DIR};
+use aoc::intcode::{Intcode, IntcodeBuilder};
+use aoc::bench::Suite;
+use std::path::PathBuf;
+
+fn boost(program: &[i64], mode: i64) -> i64 {
+    let mut vm = IntcodeBuilder::new()
+        .with_memory_size(4096)
+        .with_memory(program)
+        .with_inputs(&[mode])
+        .build();
+    vm.run();
+    vm.outputs.pop().unwrap()
+}
+
+fn main() {
+    let mut suite = Suite::from_args("intcode");
+    let inputs = Source::Directory(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DIR));
+    let text = match inputs.load(2019, 9) {
+        Ok(input) => input,
+        Err(message) => {
+            eprintln!("skipping intcode benchmarks: {}", message);
+            return;
+        }
+    };
+    let program = Intcode::compile(&text);
+    suite.bench("compile", || {
+        Intcode::compile(&text);
+    });
+    suite.bench("boost-test-mode", || {
+        boost(&program, 1);
+    });
+    suite.bench("boost-sensor-boost", || {
+        boost(&program, 2);
+    });
+    suite.finish();
+}
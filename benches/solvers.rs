@@ -0,0 +1,40 @@
+//! Parse, part 1 and part 2 times for every day with an input on disk.
+
+use aoc::bench::Suite;
+use aoc::input::{Source, DEFAULT_DIR};
+use aoc::registry;
+use aoc::timing::Timing;
+use std::path::PathBuf;
+
+fn main() {
+    let mut suite = Suite::from_args("solvers");
+    let inputs = Source::Directory(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_DIR));
+    for ((year, day), entry) in registry::registry() {
+        let name = format!("{}-{}", year, day);
+        if !suite.includes(&name) {
+            continue;
+        }
+        let input = match inputs.load(year, day) {
+            Ok(input) => input,
+            Err(message) => {
+                eprintln!("skipping {}: {}", name, message);
+                continue;
+            }
+        };
+        // The first run sizes the sample count; solve() times each step
+        // separately on every repeat.
+        let parts: Vec<usize> = (1..=entry.parts).collect();
+        let first = (entry.solve)(&input, &parts, 1);
+        let total = first.parse.samples[0] + first.parts.iter().map(|x| x.timing.samples[0]).sum();
+        let run = (entry.solve)(&input, &parts, aoc::bench::sample_count(total));
+        suite.record(&format!("{}-parse", name), &run.parse);
+        for part in &run.parts {
+            let timing = match &part.outcome {
+                Ok(_) => part.timing.clone(),
+                Err(_) => Timing::new()
+            };
+            suite.record(&format!("{}-part{}", name, part.part), &timing);
+        }
+    }
+    suite.finish();
+}
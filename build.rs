@@ -0,0 +1,65 @@
+//! Generates the solver registry from the day modules on disk: every
+//! `src/yYYYY/dayN.rs` must define `pub struct DayN` implementing `Solver`,
+//! and is registered as `(YYYY, N)`. Adding a day is just adding the file.
+
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn number(name: &str, prefix: &str, suffix: &str) -> Option<usize> {
+    let digits = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    digits.parse().ok()
+}
+
+fn entries(dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut entries: Vec<(String, PathBuf)> = fs::read_dir(dir)
+        .unwrap_or_else(|x| panic!("failed to read {}: {}", dir.display(), x))
+        .map(|entry| entry.unwrap().path())
+        .map(|path| (path.file_name().unwrap().to_string_lossy().into_owned(), path))
+        .collect();
+    entries.sort();
+    entries
+}
+
+fn main() {
+    let src = Path::new(&env::var("CARGO_MANIFEST_DIR").unwrap()).join("src");
+    println!("cargo:rerun-if-changed={}", src.display());
+
+    let mut years = Vec::new();
+    for (name, path) in entries(&src) {
+        if let Some(year) = number(&name, "y", "") {
+            let mut days: Vec<(usize, PathBuf)> = entries(&path).into_iter()
+                .filter_map(|(name, path)| number(&name, "day", ".rs").map(|day| (day, path)))
+                .collect();
+            days.sort();
+            years.push((year, days));
+        }
+    }
+    years.sort();
+
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs; do not edit.").unwrap();
+    for (year, days) in &years {
+        writeln!(code, "pub mod y{} {{", year).unwrap();
+        for (day, path) in days {
+            writeln!(code, "    #[path = {:?}]", path.display().to_string()).unwrap();
+            writeln!(code, "    pub mod day{};", day).unwrap();
+        }
+        writeln!(code, "}}").unwrap();
+    }
+    writeln!(code, "pub fn register(registry: &mut Registry) {{").unwrap();
+    for (year, days) in &years {
+        for (day, _) in days {
+            let solver = format!("y{}::day{}::Day{}", year, day, day);
+            writeln!(code, "    registry.insert(({}, {}), Entry {{ solve: crate::solver::solve::<{}>, parts: <{} as crate::solver::Solver>::PARTS }});", year, day, solver, solver).unwrap();
+        }
+    }
+    writeln!(code, "}}").unwrap();
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("registry.rs");
+    fs::write(out, code).unwrap();
+}
@@ -0,0 +1,260 @@
+//! Recorded answers, one `answers/YEAR/DAY.toml` file per day:
+//!
+//! ```toml
+//! part1 = 3224048
+//! part2 = '''
+//! ...rendered image...
+//! '''
+//! ```
+//!
+//! Answers the site rejected are kept beside them in `DAY.rejected`, so that
+//! they're never submitted twice.
+
+use crate::solver::Answer;
+use crate::toml::{self, Document, Value};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIR: &str = "answers";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Verdict {
+    Correct,
+    Wrong(String),
+    Unrecorded
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Answers {
+    pub part1: Option<String>,
+    pub part2: Option<String>
+}
+
+impl Answers {
+
+    /// Loads the answers for `day`; a missing file means nothing is recorded.
+    pub fn load(dir: &Path, year: usize, day: usize) -> Result<Answers, String> {
+        Answers::load_file(&path(dir, year, day, "toml"))
+    }
+
+    /// Records `answer` for `part`, keeping the rest of the file.
+    pub fn record(dir: &Path, year: usize, day: usize, part: usize, answer: &str) -> Result<(), String> {
+        let path = path(dir, year, day, "toml");
+        let mut document = match fs::read_to_string(&path) {
+            Ok(text) => toml::parse(&text).map_err(|x| format!("{}: {}", path.display(), x))?,
+            Err(ref x) if x.kind() == io::ErrorKind::NotFound => Document::new(),
+            Err(x) => return Err(format!("failed to read {}: {}", path.display(), x))
+        };
+        let value = match answer.parse() {
+            Ok(value) => Value::Integer(value),
+            Err(_) => Value::String(answer.to_string())
+        };
+        document.set("", &format!("part{}", part), value);
+        write(&path, &document.to_string())
+    }
+
+    pub fn load_file(path: &Path) -> Result<Answers, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(ref x) if x.kind() == io::ErrorKind::NotFound => return Ok(Answers::default()),
+            Err(x) => return Err(format!("failed to read {}: {}", path.display(), x))
+        };
+        let document = toml::parse(&text).map_err(|x| format!("{}: {}", path.display(), x))?;
+        let part = |key: &str| match document.get("", key) {
+            Some(Value::Boolean(_)) => Err(format!("{}: {} must be a number or string", path.display(), key)),
+            Some(value) => Ok(Some(expected(value))),
+            None => Ok(None)
+        };
+        Ok(Answers {
+            part1: part("part1")?,
+            part2: part("part2")?
+        })
+    }
+
+    pub fn get(&self, part: usize) -> Option<&str> {
+        match part {
+            1 => self.part1.as_deref(),
+            _ => self.part2.as_deref()
+        }
+    }
+
+    pub fn check(&self, part: usize, answer: &Answer) -> Verdict {
+        match self.get(part) {
+            None => Verdict::Unrecorded,
+            Some(expected) if normalize(expected) == normalize(&answer.to_string()) => Verdict::Correct,
+            Some(expected) => Verdict::Wrong(expected.to_string())
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Rejection {
+    Wrong,
+    TooHigh,
+    TooLow
+}
+
+impl Rejection {
+
+    fn name(self) -> &'static str {
+        match self {
+            Rejection::Wrong => "wrong",
+            Rejection::TooHigh => "too-high",
+            Rejection::TooLow => "too-low"
+        }
+    }
+
+}
+
+/// Rejected answers for one day, stored one per line as
+/// `PART<tab>wrong|too-high|too-low<tab>ANSWER`.
+#[derive(Debug, Default, PartialEq)]
+pub struct Rejections {
+    pub entries: Vec<(usize, Rejection, String)>
+}
+
+impl Rejections {
+
+    pub fn load(dir: &Path, year: usize, day: usize) -> Result<Rejections, String> {
+        let path = path(dir, year, day, "rejected");
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref x) if x.kind() == io::ErrorKind::NotFound => return Ok(Rejections::default()),
+            Err(x) => return Err(format!("failed to read {}: {}", path.display(), x))
+        };
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate().filter(|(_, x)| !x.is_empty()) {
+            let mut fields = line.splitn(3, '\t');
+            let part = fields.next().and_then(|x| x.parse().ok());
+            let rejection = fields.next().and_then(|x| match x {
+                "wrong" => Some(Rejection::Wrong),
+                "too-high" => Some(Rejection::TooHigh),
+                "too-low" => Some(Rejection::TooLow),
+                _ => None
+            });
+            match (part, rejection, fields.next()) {
+                (Some(part), Some(rejection), Some(answer)) => entries.push((part, rejection, answer.to_string())),
+                _ => return Err(format!("{}: line {}: expected `PART<tab>VERDICT<tab>ANSWER`", path.display(), index + 1))
+            }
+        }
+        Ok(Rejections {
+            entries
+        })
+    }
+
+    pub fn save(&self, dir: &Path, year: usize, day: usize) -> Result<(), String> {
+        let text: String = self.entries.iter()
+            .map(|(part, rejection, answer)| format!("{}\t{}\t{}\n", part, rejection.name(), answer))
+            .collect();
+        write(&path(dir, year, day, "rejected"), &text)
+    }
+
+    pub fn add(&mut self, part: usize, rejection: Rejection, answer: &str) {
+        self.entries.push((part, rejection, answer.to_string()));
+    }
+
+    /// Explains why `answer` is bound to be rejected, either because it was
+    /// submitted before or because an earlier answer was already too high or
+    /// too low.
+    pub fn check(&self, part: usize, answer: &str) -> Option<String> {
+        let number = answer.parse::<i64>().ok();
+        self.entries.iter()
+            .filter(|(x, _, _)| *x == part)
+            .find_map(|(_, rejection, previous)| {
+                if previous == answer {
+                    return Some(format!("{} was already rejected ({})", answer, rejection.name()));
+                }
+                let (number, previous_number) = (number?, previous.parse::<i64>().ok()?);
+                match rejection {
+                    Rejection::TooHigh if number >= previous_number => Some(format!("{} is too high: {} already was", answer, previous)),
+                    Rejection::TooLow if number <= previous_number => Some(format!("{} is too low: {} already was", answer, previous)),
+                    _ => None
+                }
+            })
+    }
+
+}
+
+fn path(dir: &Path, year: usize, day: usize, extension: &str) -> PathBuf {
+    dir.join(year.to_string()).join(format!("{}.{}", day, extension))
+}
+
+fn write(path: &Path, text: &str) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|x| format!("failed to create {}: {}", parent.display(), x))?;
+    }
+    fs::write(path, text).map_err(|x| format!("failed to write {}: {}", path.display(), x))
+}
+
+/// Editors tend to strip trailing spaces, which rendered images are full of.
+fn normalize(text: &str) -> Vec<&str> {
+    text.lines().map(|x| x.trim_end()).collect()
+}
+
+fn expected(value: &Value) -> String {
+    match value {
+        // Images are stored with a trailing newline to keep the closing quotes
+        // on their own line.
+        Value::String(text) => text.strip_suffix('\n').unwrap_or(text).to_string(),
+        value => value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+    use crate::solver::Image;
+
+    #[test]
+    fn checks_answers_against_recorded_values() {
+        let dir = TempDir::new("answers");
+        fs::create_dir_all(dir.join("2019")).unwrap();
+        fs::write(dir.join("2019/8.toml"), "part1 = 1820\npart2 = '''\n\u{2588}\n \u{2588}\n'''\n").unwrap();
+        let answers = Answers::load(&dir, 2019, 8).unwrap();
+        let image = Image {
+            width: 2,
+            height: 2,
+            pixels: vec![true, false, false, true]
+        };
+        assert_eq!(Verdict::Correct, answers.check(1, &Answer::Integer(1820)));
+        assert_eq!(Verdict::Wrong("1820".to_string()), answers.check(1, &Answer::Unsolved));
+        assert_eq!(Verdict::Correct, answers.check(2, &Answer::Image(image)));
+        assert_eq!(Answers::default(), Answers::load(&dir, 2019, 9).unwrap());
+        assert_eq!(Verdict::Unrecorded, Answers::default().check(1, &Answer::Integer(1)));
+    }
+
+    #[test]
+    fn records_accepted_answers() {
+        let dir = TempDir::new("record");
+        Answers::record(&dir, 2019, 6, 1, "142915").unwrap();
+        Answers::record(&dir, 2019, 6, 2, "283").unwrap();
+        Answers::record(&dir, 2019, 11, 2, "HJKJHRJZ").unwrap();
+        assert_eq!("part1 = 142915\npart2 = 283\n", fs::read_to_string(dir.join("2019/6.toml")).unwrap());
+        assert_eq!(Some("HJKJHRJZ"), Answers::load(&dir, 2019, 11).unwrap().get(2));
+    }
+
+    #[test]
+    fn remembers_rejected_answers() {
+        let dir = TempDir::new("rejected");
+        let mut rejections = Rejections::load(&dir, 2019, 1).unwrap();
+        rejections.add(1, Rejection::TooHigh, "500");
+        rejections.add(1, Rejection::TooLow, "100");
+        rejections.add(2, Rejection::Wrong, "ABC");
+        rejections.save(&dir, 2019, 1).unwrap();
+        let rejections = Rejections::load(&dir, 2019, 1).unwrap();
+        assert_eq!(3, rejections.entries.len());
+        assert_eq!(Some("500 was already rejected (too-high)".to_string()), rejections.check(1, "500"));
+        assert_eq!(Some("600 is too high: 500 already was".to_string()), rejections.check(1, "600"));
+        assert_eq!(Some("99 is too low: 100 already was".to_string()), rejections.check(1, "99"));
+        assert_eq!(None, rejections.check(1, "300"));
+        assert_eq!(None, rejections.check(2, "600"));
+        assert!(rejections.check(2, "ABC").is_some());
+        fs::write(dir.join("2019/1.rejected"), "1\tclose\t5\n").unwrap();
+        assert!(Rejections::load(&dir, 2019, 1).is_err());
+    }
+
+}
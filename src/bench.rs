@@ -0,0 +1,230 @@
+//! A small benchmark harness for the `cargo bench` targets in `benches/`.
+//!
+//! Each benchmark is sampled repeatedly and summarized by its median. Results
+//! are saved as TOML under `aoc-bench/` in the target directory
+//! (`$CARGO_TARGET_DIR`, or `target/`): every run overwrites the `latest`
+//! baseline, and `--save-baseline NAME` keeps a named copy. Results are
+//! compared against `--baseline NAME`, or the previous `latest` run.
+//!
+//! ```text
+//! cargo bench                              # run everything, compare to last run
+//! cargo bench -- --save-baseline before    # record a baseline
+//! cargo bench -- --baseline before 2019-9  # compare matching benchmarks
+//! ```
+
+use crate::timing::{format_duration, Timing};
+use crate::toml::{self, Document, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// Time spent sampling each benchmark, after the first run.
+const TARGET: Duration = Duration::from_millis(500);
+const MIN_SAMPLES: usize = 5;
+const MAX_SAMPLES: usize = 200;
+const LATEST: &str = "latest";
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    /// Only benchmarks whose name contains this run.
+    pub filter: Option<String>,
+    pub save: Option<String>,
+    pub baseline: Option<String>
+}
+
+impl Options {
+
+    /// Parses harness arguments; `--bench`, which cargo passes to every bench
+    /// target, is ignored.
+    pub fn parse(args: &[String]) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--bench" => {},
+                "--save-baseline" => options.save = Some(args.next().ok_or("missing value for --save-baseline")?.clone()),
+                "--baseline" => options.baseline = Some(args.next().ok_or("missing value for --baseline")?.clone()),
+                _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                _ => options.filter = Some(arg.clone())
+            }
+        }
+        for name in options.save.iter().chain(options.baseline.iter()) {
+            if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+                return Err(format!("invalid baseline name `{}`", name));
+            }
+        }
+        Ok(options)
+    }
+
+}
+
+/// Number of samples to take of a step whose first run took `first`.
+pub fn sample_count(first: Duration) -> usize {
+    let nanos = first.as_nanos().max(1);
+    ((TARGET.as_nanos() / nanos) as usize).clamp(MIN_SAMPLES, MAX_SAMPLES)
+}
+
+/// Describes the change from `baseline` to `current`.
+pub fn compare(current: Duration, baseline: Duration) -> String {
+    let change = (current.as_secs_f64() / baseline.as_secs_f64().max(f64::MIN_POSITIVE) - 1.0) * 100.0;
+    let verdict = if change.abs() < 5.0 { "no change" } else if change < 0.0 { "faster" } else { "slower" };
+    format!("{:+.1}% ({})", change, verdict)
+}
+
+/// One bench target's benchmarks, saved as one table in each baseline file.
+pub struct Suite {
+    name: String,
+    options: Options,
+    dir: PathBuf,
+    baseline: Option<Document>,
+    results: Vec<(String, Duration)>
+}
+
+impl Suite {
+
+    /// Creates a suite from the process arguments, exiting on bad usage.
+    pub fn from_args(name: &str) -> Suite {
+        let args: Vec<String> = std::env::args().skip(1).collect();
+        let options = Options::parse(&args).unwrap_or_else(|message| {
+            eprintln!("error: {}", message);
+            eprintln!("usage: cargo bench -- [FILTER] [--save-baseline NAME] [--baseline NAME]");
+            std::process::exit(2);
+        });
+        let target = std::env::var_os("CARGO_TARGET_DIR").filter(|x| !x.is_empty()).map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("target"));
+        let dir = target.join("aoc-bench");
+        Suite::new(name, options, dir)
+    }
+
+    pub fn new(name: &str, options: Options, dir: PathBuf) -> Suite {
+        let baseline_name = options.baseline.clone().unwrap_or_else(|| LATEST.to_string());
+        let baseline = fs::read_to_string(dir.join(format!("{}.toml", baseline_name))).ok()
+            .and_then(|text| toml::parse(&text).ok());
+        if options.baseline.is_some() && baseline.is_none() {
+            eprintln!("warning: no saved baseline `{}`", baseline_name);
+        }
+        Suite {
+            name: name.to_string(),
+            options,
+            dir,
+            baseline,
+            results: Vec::new()
+        }
+    }
+
+    pub fn includes(&self, name: &str) -> bool {
+        self.options.filter.as_ref().map_or(true, |x| name.contains(x.as_str()))
+    }
+
+    /// Samples `f` and records its median time.
+    pub fn bench<F: FnMut()>(&mut self, name: &str, mut f: F) {
+        if !self.includes(name) {
+            return;
+        }
+        let mut sample = || {
+            let start = Instant::now();
+            f();
+            start.elapsed()
+        };
+        let count = sample_count(sample());
+        let timing = Timing {
+            samples: (0..count).map(|_| sample()).collect()
+        };
+        self.record(name, &timing);
+    }
+
+    /// Records samples taken elsewhere, e.g. by `solver::solve`.
+    pub fn record(&mut self, name: &str, timing: &Timing) {
+        let median = match timing.median() {
+            Some(median) => median,
+            None => return
+        };
+        let previous = self.baseline.as_ref()
+            .and_then(|x| x.get(&self.name, name))
+            .and_then(|x| match x {
+                Value::Integer(nanos) => Some(Duration::from_nanos(*nanos as u64)),
+                _ => None
+            });
+        let comparison = previous.map(|x| compare(median, x)).unwrap_or_default();
+        println!("{:<24} {:>10}  median of {:<4} {}", name, format_duration(median), timing.samples.len(), comparison);
+        self.results.push((name.to_string(), median));
+    }
+
+    /// Saves the results as the `latest` baseline and any named one. Entries
+    /// of other suites, and of benchmarks filtered out of this run, are kept.
+    pub fn finish(self) {
+        let mut names = vec![LATEST.to_string()];
+        names.extend(self.options.save.iter().cloned());
+        if let Err(x) = fs::create_dir_all(&self.dir) {
+            eprintln!("warning: failed to create {}: {}", self.dir.display(), x);
+            return;
+        }
+        for name in names {
+            let path = self.dir.join(format!("{}.toml", name));
+            let mut document = fs::read_to_string(&path).ok()
+                .and_then(|text| toml::parse(&text).ok())
+                .unwrap_or_default();
+            for (bench, median) in &self.results {
+                document.set(&self.name, bench, Value::Integer(median.as_nanos() as i64));
+            }
+            if let Err(x) = fs::write(&path, document.to_string()) {
+                eprintln!("warning: failed to write {}: {}", path.display(), x);
+            }
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+
+    fn args(args: &str) -> Vec<String> {
+        args.split_whitespace().map(|x| x.to_string()).collect()
+    }
+
+    #[test]
+    fn parses_options() {
+        let options = Options {
+            filter: Some("2019-9".to_string()),
+            save: Some("before".to_string()),
+            baseline: None
+        };
+        assert_eq!(Ok(options), Options::parse(&args("--bench 2019-9 --save-baseline before")));
+        assert!(Options::parse(&args("--baseline")).is_err());
+        assert!(Options::parse(&args("--save-baseline a/b")).is_err());
+        assert!(Options::parse(&args("--verbose")).is_err());
+    }
+
+    #[test]
+    fn compares_against_baseline() {
+        assert_eq!("+50.0% (slower)", compare(Duration::from_millis(30), Duration::from_millis(20)));
+        assert_eq!("-50.0% (faster)", compare(Duration::from_millis(10), Duration::from_millis(20)));
+        assert_eq!("+2.0% (no change)", compare(Duration::from_micros(102), Duration::from_micros(100)));
+        assert_eq!(MIN_SAMPLES, sample_count(Duration::from_secs(2)));
+        assert_eq!(MAX_SAMPLES, sample_count(Duration::from_nanos(10)));
+    }
+
+    #[test]
+    fn saves_and_reloads_baselines() {
+        let dir = TempDir::new("bench");
+        let options = Options {
+            save: Some("before".to_string()),
+            ..Default::default()
+        };
+        let mut suite = Suite::new("solvers", options, dir.to_path_buf());
+        suite.bench("noop", || {});
+        suite.record("fixed", &Timing {
+            samples: vec![Duration::from_nanos(1500)]
+        });
+        suite.finish();
+        let document = toml::parse(&fs::read_to_string(dir.join("before.toml")).unwrap()).unwrap();
+        assert_eq!(Some(&Value::Integer(1500)), document.get("solvers", "fixed"));
+        assert!(document.get("solvers", "noop").is_some());
+        let suite = Suite::new("solvers", Options::default(), dir.to_path_buf());
+        assert!(suite.baseline.as_ref().unwrap().get("solvers", "fixed").is_some());
+    }
+
+}
@@ -0,0 +1,598 @@
+use crate::repl::Mode;
+use aoc::config::Config;
+use aoc::input::Source;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+pub const USAGE: &str = "\
+usage: aoc [run] [DAYS] [OPTIONS] [--inputs DIR | --input FILE | --example NAME]
+       aoc verify [DAYS] [OPTIONS] [--inputs DIR] [--answers DIR]
+       aoc watch DAY [OPTIONS] [--inputs DIR | --input FILE]
+       aoc new-day DAY [--year YEAR] [--inputs DIR]
+       aoc intcode FILE [--ascii] [--memory N]
+       aoc fetch DAY [--year YEAR] [--url URL]
+       aoc submit DAY PART [--year YEAR] [--answer ANSWER] [--url URL]
+                  [--inputs DIR] [--answers DIR]
+       aoc status [--year YEAR] [--answers DIR]
+       aoc list
+       aoc help
+
+DAYS is a single day (7) or an inclusive range (3-5), also accepted as
+--day DAYS; defaults to every day of the year. Inputs are read from
+DIR/YEAR/DAY.txt (default: inputs/YEAR/DAY.txt). --input reads a single day's
+input from FILE, or from stdin if FILE is `-`. --example runs the named example
+from DIR/YEAR/examples/DAY/NAME.txt instead, checked against NAME.toml by
+verify; `aoc list` shows the available examples.
+
+verify checks each answer against DIR/YEAR/DAY.toml (default: answers/...).
+With any --inputs DIR other than the default, answers are only checked when
+--answers is given.
+
+watch re-runs a day (with the given options) and its tests (through cargo)
+whenever its module or input file changes.
+
+new-day writes src/yYYYY/dayN.rs from a template plus an empty input file; the
+build script registers it automatically.
+
+status shows every day of the year: each part's last result and runtime, as
+recorded by earlier runs from the default inputs directory, and the stars
+earned, i.e. the answers recorded in DIR (default: answers).
+
+intcode runs the Intcode program in FILE, printing outputs as they arrive and
+prompting for input whenever the program needs it: comma or space separated
+integers, or with --ascii, a line of text. --memory sets the number of memory
+cells (default: 65536).
+
+fetch downloads a day's input from the site into a per-user cache
+($XDG_CACHE_HOME/aoc or ~/.cache/aoc), which is read whenever the default
+inputs directory has no file for the day. Cached inputs are never downloaded again.
+
+submit solves a part (or takes --answer) and posts it to the site.
+
+Both talk to the site at URL (default: $AOC_URL, or `url` in the config) with
+the session cookie in $AOC_SESSION. There is no default url: only plain http
+is supported, so it must point at an http proxy or mirror of the site.
+Accepted answers are recorded in the answers directory; rejected ones are
+remembered in DAY.rejected beside them and never submitted again.
+
+options:
+  -y, --year YEAR   select the event year (default: the latest one)
+  -p, --part N      run only part 1 or 2
+  -t, --time        print a table of parse and part timings (--no-time to
+                    turn off a config default)
+  -r, --repeat N    run each step N times and report min / median times
+  -f, --format F    output `text` (default) or `json`, which includes each
+                    answer's status against the recorded answers (see
+                    --answers) when the input is from DIR or an example
+  -j, --jobs N      run up to N days at once (0: one per CPU); results are
+                    still printed in day order, but timings will be noisier
+
+defaults for --year, --inputs, --format, --time and the site url and session
+are read from ~/.config/aoc/config.toml and then aoc.toml in the current
+directory or a parent; flags override them.";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Run(Selection),
+    Verify(Selection),
+    Watch(Selection),
+    NewDay {
+        year: Option<usize>,
+        day: usize,
+        inputs: Option<PathBuf>
+    },
+    Intcode {
+        program: PathBuf,
+        mode: Mode,
+        memory: Option<usize>
+    },
+    Status {
+        year: Option<usize>,
+        answers: Option<PathBuf>
+    },
+    Fetch {
+        year: Option<usize>,
+        day: usize,
+        url: Option<String>
+    },
+    Submit {
+        year: Option<usize>,
+        day: usize,
+        part: usize,
+        answer: Option<String>,
+        url: Option<String>,
+        inputs: Option<PathBuf>,
+        answers: Option<PathBuf>
+    },
+    List,
+    Help
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Selection {
+    pub year: Option<usize>,
+    pub days: Option<RangeInclusive<usize>>,
+    pub part: Option<usize>,
+    pub input: Source,
+    pub answers: Option<PathBuf>,
+    pub time: bool,
+    pub repeat: Option<usize>,
+    pub jobs: Option<usize>,
+    pub format: Format
+}
+
+impl Selection {
+
+    pub fn includes_day(&self, day: usize) -> bool {
+        self.days.as_ref().map_or(true, |x| x.contains(&day))
+    }
+
+    pub fn includes_part(&self, part: usize) -> bool {
+        self.part.map_or(true, |x| x == part)
+    }
+
+}
+
+/// Parses the arguments, with defaults from `config`.
+pub fn parse(args: &[String], config: &Config) -> Result<Command, String> {
+    let mut args = args.iter().map(|x| x.as_str()).peekable();
+    let command = match args.peek() {
+        Some(&"run") | Some(&"verify") | Some(&"watch") | Some(&"new-day") => args.next().unwrap(),
+        Some(&"intcode") | Some(&"fetch") | Some(&"submit") | Some(&"status") => args.next().unwrap(),
+        Some(&"list") | Some(&"help") | Some(&"--help") | Some(&"-h") => args.next().unwrap(),
+        _ => "run"
+    };
+
+    match command {
+        "list" | "help" | "--help" | "-h" => {
+            if let Some(arg) = args.next() {
+                return Err(format!("unexpected argument `{}`", arg));
+            }
+            Ok(if command == "list" { Command::List } else { Command::Help })
+        },
+        "new-day" => {
+            let (mut year, mut day, mut inputs) = (config.year, None, config.inputs.clone());
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--year" | "-y" => {
+                        let value = args.next().ok_or("missing value for --year")?;
+                        year = Some(value.parse().map_err(|_| format!("invalid year `{}`", value))?);
+                    },
+                    "--inputs" => inputs = Some(PathBuf::from(args.next().ok_or("missing value for --inputs")?)),
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if day.is_none() => day = Some(arg.parse().map_err(|_| format!("invalid day `{}`", arg))?),
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            let day = day.ok_or("missing day for new-day")?;
+            Ok(Command::NewDay {
+                year,
+                day,
+                inputs
+            })
+        },
+        "intcode" => {
+            let (mut program, mut mode, mut memory) = (None, Mode::Numeric, None);
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--ascii" | "-a" => mode = Mode::Ascii,
+                    "--memory" | "-m" => {
+                        let value = args.next().ok_or("missing value for --memory")?;
+                        memory = match value.parse::<usize>() {
+                            Ok(size) if size > 0 => Some(size),
+                            _ => return Err(format!("invalid memory size `{}`", value))
+                        };
+                    },
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if program.is_none() => program = Some(PathBuf::from(arg)),
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            let program = program.ok_or("missing program file for intcode")?;
+            Ok(Command::Intcode {
+                program,
+                mode,
+                memory
+            })
+        },
+        "status" => {
+            let (mut year, mut answers) = (config.year, None);
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--year" | "-y" => {
+                        let value = args.next().ok_or("missing value for --year")?;
+                        year = Some(value.parse().map_err(|_| format!("invalid year `{}`", value))?);
+                    },
+                    "--answers" => answers = Some(PathBuf::from(args.next().ok_or("missing value for --answers")?)),
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            Ok(Command::Status {
+                year,
+                answers
+            })
+        },
+        "fetch" => {
+            let (mut year, mut day, mut url) = (config.year, None, None);
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--year" | "-y" => {
+                        let value = args.next().ok_or("missing value for --year")?;
+                        year = Some(value.parse().map_err(|_| format!("invalid year `{}`", value))?);
+                    },
+                    "--url" => url = Some(args.next().ok_or("missing value for --url")?.to_string()),
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if day.is_none() => day = Some(arg.parse().map_err(|_| format!("invalid day `{}`", arg))?),
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            Ok(Command::Fetch {
+                year,
+                day: day.ok_or("missing day for fetch")?,
+                url
+            })
+        },
+        "submit" => {
+            let (mut year, mut day, mut part, mut answer, mut url) = (config.year, None, None, None, None);
+            let (mut inputs, mut answers) = (config.inputs.clone(), None);
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--year" | "-y" => {
+                        let value = args.next().ok_or("missing value for --year")?;
+                        year = Some(value.parse().map_err(|_| format!("invalid year `{}`", value))?);
+                    },
+                    "--answer" => answer = Some(args.next().ok_or("missing value for --answer")?.to_string()),
+                    "--url" => url = Some(args.next().ok_or("missing value for --url")?.to_string()),
+                    "--inputs" => inputs = Some(PathBuf::from(args.next().ok_or("missing value for --inputs")?)),
+                    "--answers" => answers = Some(PathBuf::from(args.next().ok_or("missing value for --answers")?)),
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if day.is_none() => day = Some(arg.parse().map_err(|_| format!("invalid day `{}`", arg))?),
+                    _ if part.is_none() => part = match arg {
+                        "1" => Some(1),
+                        "2" => Some(2),
+                        _ => return Err(format!("invalid part `{}`, expected 1 or 2", arg))
+                    },
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            Ok(Command::Submit {
+                year,
+                day: day.ok_or("missing day for submit")?,
+                part: part.ok_or("missing part for submit")?,
+                answer,
+                url,
+                inputs,
+                answers
+            })
+        },
+        _ => {
+            let mut selection = Selection {
+                year: config.year,
+                input: config.inputs.clone().map(Source::Directory).unwrap_or_default(),
+                time: config.time.unwrap_or(false),
+                format: if config.format.as_deref() == Some("json") { Format::Json } else { Format::Text },
+                ..Default::default()
+            };
+            let mut example = None;
+            while let Some(arg) = args.next() {
+                match arg {
+                    "--part" | "-p" => {
+                        let value = args.next().ok_or("missing value for --part")?;
+                        selection.part = match value {
+                            "1" => Some(1),
+                            "2" => Some(2),
+                            _ => return Err(format!("invalid part `{}`, expected 1 or 2", value))
+                        };
+                    },
+                    "--year" | "-y" => {
+                        let value = args.next().ok_or("missing value for --year")?;
+                        selection.year = Some(value.parse().map_err(|_| format!("invalid year `{}`", value))?);
+                    },
+                    "--day" | "-d" => {
+                        let value = args.next().ok_or("missing value for --day")?;
+                        if selection.days.is_some() {
+                            return Err(format!("unexpected argument `{}`", value));
+                        }
+                        selection.days = Some(days(value)?);
+                    },
+                    "--time" | "-t" => selection.time = true,
+                    "--no-time" => selection.time = false,
+                    "--repeat" | "-r" => {
+                        let value = args.next().ok_or("missing value for --repeat")?;
+                        selection.repeat = match value.parse::<usize>() {
+                            Ok(count) if count > 0 => Some(count),
+                            _ => return Err(format!("invalid repeat count `{}`", value))
+                        };
+                    },
+                    "--format" | "-f" => {
+                        selection.format = match args.next().ok_or("missing value for --format")? {
+                            "text" => Format::Text,
+                            "json" => Format::Json,
+                            value => return Err(format!("invalid format `{}`, expected text or json", value))
+                        };
+                    },
+                    "--jobs" | "-j" => {
+                        let value = args.next().ok_or("missing value for --jobs")?;
+                        selection.jobs = Some(value.parse().map_err(|_| format!("invalid job count `{}`", value))?);
+                    },
+                    "--inputs" => {
+                        let value = args.next().ok_or("missing value for --inputs")?;
+                        selection.input = Source::Directory(PathBuf::from(value));
+                    },
+                    "--answers" if command != "watch" => {
+                        let value = args.next().ok_or("missing value for --answers")?;
+                        selection.answers = Some(PathBuf::from(value));
+                    },
+                    "--example" | "-e" => example = Some(args.next().ok_or("missing value for --example")?.to_string()),
+                    "--input" | "-i" => {
+                        let value = args.next().ok_or("missing value for --input")?;
+                        selection.input = match value {
+                            "-" => Source::Stdin,
+                            _ => Source::File(PathBuf::from(value))
+                        };
+                    },
+                    _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+                    _ if selection.days.is_none() => selection.days = Some(days(arg)?),
+                    _ => return Err(format!("unexpected argument `{}`", arg))
+                }
+            }
+            if let Some(name) = example {
+                selection.input = match selection.input {
+                    Source::Directory(dir) => Source::Example {
+                        dir,
+                        name
+                    },
+                    _ => return Err("--example can't be combined with --input".to_string())
+                };
+            }
+            if selection.answers.is_some() && command != "verify" && selection.format != Format::Json {
+                return Err("--answers is only used by verify and --format json".to_string());
+            }
+            let single_day = selection.days.as_ref().is_some_and(|x| x.start() == x.end());
+            if !single_day && !matches!(selection.input, Source::Directory(_) | Source::Example { .. }) {
+                return Err("--input needs a single day, e.g. `aoc run 7 --input FILE`".to_string());
+            }
+            match command {
+                "verify" => Ok(Command::Verify(selection)),
+                "watch" if !single_day => Err("watch needs a single day, e.g. `aoc watch 7`".to_string()),
+                "watch" if selection.input == Source::Stdin => Err("watch can't read input from stdin".to_string()),
+                "watch" => Ok(Command::Watch(selection)),
+                _ => Ok(Command::Run(selection))
+            }
+        }
+    }
+}
+
+fn days(arg: &str) -> Result<RangeInclusive<usize>, String> {
+    let day = |x: &str| x.parse::<usize>().map_err(|_| format!("invalid day `{}`", arg));
+    let mut parts = arg.splitn(2, '-');
+    let first = day(parts.next().unwrap())?;
+    let last = match parts.next() {
+        Some(last) => day(last)?,
+        None => first
+    };
+    if first > last {
+        return Err(format!("invalid day range `{}`", arg));
+    }
+    Ok(first..=last)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn parse_args(args: &str) -> Result<Command, String> {
+        parse_with(args, &Config::default())
+    }
+
+    fn parse_with(args: &str, config: &Config) -> Result<Command, String> {
+        let args: Vec<String> = args.split_whitespace().map(|x| x.to_string()).collect();
+        parse(&args, config)
+    }
+
+    #[test]
+    fn runs_everything_by_default() {
+        assert_eq!(Ok(Command::Run(Selection::default())), parse_args(""));
+        assert_eq!(Ok(Command::Run(Selection::default())), parse_args("run"));
+    }
+
+    #[test]
+    fn selects_days_and_parts() {
+        let selection = Selection {
+            days: Some(7..=7),
+            part: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("run 7 --part 2"));
+        let selection = Selection {
+            days: Some(3..=5),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("3-5"));
+        let selection = Selection {
+            year: Some(2019),
+            days: Some(9..=9),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("--year 2019 --day 9"));
+    }
+
+    #[test]
+    fn selects_input_source() {
+        let selection = Selection {
+            days: Some(7..=7),
+            input: Source::File(PathBuf::from("mine.txt")),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("7 --input mine.txt"));
+        let selection = Selection {
+            days: Some(7..=7),
+            input: Source::Stdin,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("7 -i -"));
+        let selection = Selection {
+            input: Source::Directory(PathBuf::from("theirs")),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("--inputs theirs"));
+        let selection = Selection {
+            input: Source::Example {
+                dir: PathBuf::from("theirs"),
+                name: "small".to_string()
+            },
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Verify(selection)), parse_args("verify --example small --inputs theirs"));
+    }
+
+    #[test]
+    fn selects_output_options() {
+        let selection = Selection {
+            time: true,
+            repeat: Some(10),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("-t --repeat 10"));
+        let selection = Selection {
+            jobs: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("--jobs 0"));
+        let selection = Selection {
+            format: Format::Json,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("--format json"));
+        let selection = Selection {
+            answers: Some(PathBuf::from("locked")),
+            format: Format::Json,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_args("--answers locked -f json"));
+    }
+
+    #[test]
+    fn applies_config_defaults_under_flags() {
+        let config = Config {
+            year: Some(2018),
+            inputs: Some(PathBuf::from("/data")),
+            format: Some("json".to_string()),
+            time: Some(true),
+            ..Default::default()
+        };
+        let selection = Selection {
+            year: Some(2018),
+            input: Source::Directory(PathBuf::from("/data")),
+            time: true,
+            format: Format::Json,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_with("", &config));
+        let selection = Selection {
+            year: Some(2019),
+            input: Source::Directory(PathBuf::from("mine")),
+            format: Format::Text,
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Run(selection)), parse_with("-y 2019 --inputs mine --no-time -f text", &config));
+        let command = Command::NewDay {
+            year: Some(2018),
+            day: 3,
+            inputs: Some(PathBuf::from("/data"))
+        };
+        assert_eq!(Ok(command), parse_with("new-day 3", &config));
+    }
+
+    #[test]
+    fn parses_other_commands() {
+        assert_eq!(Ok(Command::List), parse_args("list"));
+        assert_eq!(Ok(Command::Help), parse_args("--help"));
+        let selection = Selection {
+            days: Some(1..=1),
+            answers: Some(PathBuf::from("locked")),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Verify(selection)), parse_args("verify 1 --answers locked"));
+        let command = Command::NewDay {
+            year: Some(2020),
+            day: 3,
+            inputs: None
+        };
+        assert_eq!(Ok(command), parse_args("new-day 3 --year 2020"));
+        let selection = Selection {
+            days: Some(7..=7),
+            part: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(Ok(Command::Watch(selection)), parse_args("watch 7 -p 1"));
+        let command = Command::Intcode {
+            program: PathBuf::from("inputs/2019/9.txt"),
+            mode: Mode::Ascii,
+            memory: Some(4096)
+        };
+        assert_eq!(Ok(command), parse_args("intcode inputs/2019/9.txt --ascii --memory 4096"));
+        let command = Command::Status {
+            year: Some(2019),
+            answers: None
+        };
+        assert_eq!(Ok(command), parse_args("status --year 2019"));
+        let command = Command::Fetch {
+            year: Some(2019),
+            day: 9,
+            url: None
+        };
+        assert_eq!(Ok(command), parse_args("fetch 9 -y 2019"));
+        let command = Command::Submit {
+            year: None,
+            day: 9,
+            part: 2,
+            answer: Some("87221".to_string()),
+            url: Some("http://localhost:8000".to_string()),
+            inputs: None,
+            answers: None
+        };
+        assert_eq!(Ok(command), parse_args("submit 9 2 --answer 87221 --url http://localhost:8000"));
+    }
+
+    #[test]
+    fn rejects_invalid_arguments() {
+        assert!(parse_args("run seven").is_err());
+        assert!(parse_args("run 5-3").is_err());
+        assert!(parse_args("run 7 --part 3").is_err());
+        assert!(parse_args("run 7 --part").is_err());
+        assert!(parse_args("run 7 8").is_err());
+        assert!(parse_args("run 7 --day 8").is_err());
+        assert!(parse_args("run --year twenty").is_err());
+        assert!(parse_args("run --verbose").is_err());
+        assert!(parse_args("list 7").is_err());
+        assert!(parse_args("new-day").is_err());
+        assert!(parse_args("watch").is_err());
+        assert!(parse_args("watch 7 --input -").is_err());
+        assert!(parse_args("new-day 3-5").is_err());
+        assert!(parse_args("intcode").is_err());
+        assert!(parse_args("intcode program.txt --memory 0").is_err());
+        assert!(parse_args("fetch").is_err());
+        assert!(parse_args("status 7").is_err());
+        assert!(parse_args("submit 9").is_err());
+        assert!(parse_args("submit 9 3").is_err());
+        assert!(parse_args("run 3-5 --input mine.txt").is_err());
+        assert!(parse_args("run --input -").is_err());
+        assert!(parse_args("run 7 --input mine.txt --example small").is_err());
+        assert!(parse_args("run --answers locked").is_err());
+        assert!(parse_args("watch 7 --answers locked").is_err());
+        assert!(parse_args("run --repeat 0").is_err());
+        assert!(parse_args("run -j many").is_err());
+        assert!(parse_args("run --format yaml").is_err());
+    }
+
+}
@@ -0,0 +1,192 @@
+//! Client for an AoC-compatible site, for downloading inputs and submitting
+//! answers. The base url and session cookie come from `AOC_URL` and
+//! `AOC_SESSION`, or the config file; see [`http`](crate::http) for what urls
+//! are supported.
+
+use crate::config::Config;
+use crate::http;
+use std::env;
+use std::fmt;
+
+pub const URL_VAR: &str = "AOC_URL";
+pub const SESSION_VAR: &str = "AOC_SESSION";
+
+/// What the site made of a submitted answer.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Outcome {
+    Correct,
+    Wrong,
+    TooHigh,
+    TooLow,
+    /// Holds the site's note on how long to wait.
+    RateLimited(String),
+    /// The part is already solved, or not unlocked yet.
+    WrongLevel,
+    Unrecognized(String)
+}
+
+impl Outcome {
+
+    /// Classifies the page returned for a submission.
+    pub fn parse(page: &str) -> Outcome {
+        let text = text(page);
+        if text.contains("That's the right answer") {
+            Outcome::Correct
+        }
+        else if text.contains("That's not the right answer") {
+            if text.contains("too high") {
+                Outcome::TooHigh
+            }
+            else if text.contains("too low") {
+                Outcome::TooLow
+            }
+            else {
+                Outcome::Wrong
+            }
+        }
+        else if text.contains("answer too recently") {
+            let wait = text.find("You have")
+                .map(|start| &text[start..])
+                .map(|x| x.find("wait.").map(|end| &x[..end + 5]).unwrap_or(x))
+                .unwrap_or("");
+            Outcome::RateLimited(wait.to_string())
+        }
+        else if text.contains("solving the right level") {
+            Outcome::WrongLevel
+        }
+        else {
+            Outcome::Unrecognized(text)
+        }
+    }
+
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "that's the right answer"),
+            Outcome::Wrong => write!(f, "that's not the right answer"),
+            Outcome::TooHigh => write!(f, "that's not the right answer; it's too high"),
+            Outcome::TooLow => write!(f, "that's not the right answer; it's too low"),
+            Outcome::RateLimited(wait) if wait.is_empty() => write!(f, "answered too recently"),
+            Outcome::RateLimited(wait) => write!(f, "answered too recently; {}", wait),
+            Outcome::WrongLevel => write!(f, "this part is already solved or still locked"),
+            Outcome::Unrecognized(text) => write!(f, "unrecognized response: {}", text)
+        }
+    }
+}
+
+/// The page's `<article>`, or the whole page, with tags stripped and
+/// whitespace collapsed.
+fn text(page: &str) -> String {
+    let article = page.find("<article")
+        .and_then(|start| page[start..].find("</article>").map(|end| &page[start..start + end]))
+        .unwrap_or(page);
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in article.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+pub struct Client {
+    base: String,
+    session: String
+}
+
+impl Client {
+
+    pub fn new(base: &str, session: &str) -> Client {
+        Client {
+            base: base.trim_end_matches('/').to_string(),
+            session: session.trim().to_string()
+        }
+    }
+
+    /// Configures a client from `url`, or else the environment, or else
+    /// `config`. There's no default, as the real site needs https.
+    pub fn configure(url: Option<&str>, config: &Config) -> Result<Client, String> {
+        let base = url.map(|x| x.to_string())
+            .or_else(|| env::var(URL_VAR).ok())
+            .or_else(|| config.url.clone())
+            .ok_or_else(|| format!("no site url; pass --url, set {} or `url` in the config file to an http url", URL_VAR))?;
+        let session = env::var(SESSION_VAR).ok()
+            .or_else(|| config.session.clone())
+            .ok_or_else(|| format!("set {} or `session` in the config file to your session cookie", SESSION_VAR))?;
+        Ok(Client::new(&base, &session))
+    }
+
+    fn cookie(&self) -> String {
+        format!("session={}", self.session)
+    }
+
+    /// Downloads a day's puzzle input.
+    pub fn input(&self, year: usize, day: usize) -> Result<String, String> {
+        let url = format!("{}/{}/day/{}/input", self.base, year, day);
+        let response = http::get(&url, &[("Cookie", &self.cookie())])?;
+        match response.status {
+            200 => Ok(response.body),
+            404 => Err(format!("no input at {}; is the puzzle unlocked yet?", url)),
+            status => Err(format!("{} returned status {}: {}", url, status, response.body.lines().next().unwrap_or("")))
+        }
+    }
+
+    pub fn submit(&self, year: usize, day: usize, part: usize, answer: &str) -> Result<Outcome, String> {
+        let url = format!("{}/{}/day/{}/answer", self.base, year, day);
+        let level = part.to_string();
+        let response = http::post_form(&url, &[("Cookie", &self.cookie())], &[("level", &level), ("answer", answer)])?;
+        match response.status {
+            200 => Ok(Outcome::parse(&response.body)),
+            429 => Ok(Outcome::RateLimited(String::new())),
+            status => Err(format!("{} returned status {}", url, status))
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::http::stub;
+
+    fn page(message: &str) -> String {
+        format!("<html><body><main><article><p>{}</p></article></main></body></html>", message)
+    }
+
+    #[test]
+    fn classifies_responses() {
+        assert_eq!(Outcome::Correct, Outcome::parse(&page("That's the right answer! You are <em>one gold star</em> closer.")));
+        assert_eq!(Outcome::TooHigh, Outcome::parse(&page("That's not the right answer; your answer is too high.")));
+        assert_eq!(Outcome::TooLow, Outcome::parse(&page("That's not the right answer; your answer is too low.")));
+        assert_eq!(Outcome::Wrong, Outcome::parse(&page("That's not the right answer. If you're stuck...")));
+        let limited = page("You gave an answer too recently; you have to wait after submitting an answer before trying again. You have 37s left to wait. [Return]");
+        assert_eq!(Outcome::RateLimited("You have 37s left to wait.".to_string()), Outcome::parse(&limited));
+        assert_eq!(Outcome::WrongLevel, Outcome::parse(&page("You don't seem to be solving the right level.")));
+        assert_eq!(Outcome::Unrecognized("Huh?".to_string()), Outcome::parse("<p>Huh?</p>\n"));
+    }
+
+    #[test]
+    fn submits_answers() {
+        let (url, server) = stub::serve(vec![
+            (200, page("That's the right answer!").as_str()),
+            (429, ""),
+            (500, "oops")
+        ]);
+        let client = Client::new(&format!("{}/", url), "secret\n");
+        assert_eq!(Ok(Outcome::Correct), client.submit(2019, 9, 2, "87221"));
+        assert_eq!(Ok(Outcome::RateLimited(String::new())), client.submit(2019, 9, 2, "87221"));
+        assert!(client.submit(2019, 9, 2, "87221").is_err());
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("POST /2019/day/9/answer HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=secret\r\n"));
+        assert!(request.ends_with("level=2&answer=87221"));
+    }
+
+}
@@ -0,0 +1,154 @@
+//! Runner defaults, read from a user file (`$XDG_CONFIG_HOME/aoc/config.toml`
+//! or `~/.config/aoc/config.toml`) and then a project `aoc.toml` in the
+//! current directory or the nearest parent, whose values win:
+//!
+//! ```toml
+//! year = 2019
+//! inputs = "inputs"    # relative to the file's directory
+//! format = "json"      # or "text"
+//! time = true
+//! url = "http://localhost:8000"
+//! session = "..."      # better kept in the user file
+//! ```
+//!
+//! Command-line flags override both, and `AOC_URL` / `AOC_SESSION` override
+//! `url` and `session`.
+
+use crate::toml::{self, Value};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+pub const FILE_NAME: &str = "aoc.toml";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Config {
+    pub year: Option<usize>,
+    pub inputs: Option<PathBuf>,
+    /// `text` or `json`.
+    pub format: Option<String>,
+    pub time: Option<bool>,
+    pub url: Option<String>,
+    pub session: Option<String>
+}
+
+impl Config {
+
+    /// Parses a config file; relative paths are taken relative to `dir`.
+    pub fn parse(text: &str, dir: &Path) -> Result<Config, String> {
+        let document = toml::parse(text)?;
+        let mut config = Config::default();
+        for (table, entries) in &document.tables {
+            if !table.is_empty() {
+                return Err(format!("unexpected table [{}]", table));
+            }
+            for (key, value) in entries {
+                let error = |expected: &str| format!("{} must be {}", key, expected);
+                match (key.as_str(), value) {
+                    ("year", Value::Integer(year)) if *year > 0 => config.year = Some(*year as usize),
+                    ("year", _) => return Err(error("a year, e.g. 2019")),
+                    ("inputs", Value::String(path)) => config.inputs = Some(dir.join(path)),
+                    ("inputs", _) => return Err(error("a path")),
+                    ("format", Value::String(format)) if format == "text" || format == "json" => config.format = Some(format.clone()),
+                    ("format", _) => return Err(error("\"text\" or \"json\"")),
+                    ("time", Value::Boolean(time)) => config.time = Some(*time),
+                    ("time", _) => return Err(error("true or false")),
+                    ("url", Value::String(url)) => config.url = Some(url.clone()),
+                    ("url", _) => return Err(error("a url")),
+                    ("session", Value::String(session)) => config.session = Some(session.clone()),
+                    ("session", _) => return Err(error("a string")),
+                    _ => return Err(format!("unknown key `{}`", key))
+                }
+            }
+        }
+        Ok(config)
+    }
+
+    /// Values set in `other` replace those in `self`.
+    pub fn merge(self, other: Config) -> Config {
+        Config {
+            year: other.year.or(self.year),
+            inputs: other.inputs.or(self.inputs),
+            format: other.format.or(self.format),
+            time: other.time.or(self.time),
+            url: other.url.or(self.url),
+            session: other.session.or(self.session)
+        }
+    }
+
+    /// Loads the user and project files, if any; see the module docs.
+    pub fn load() -> Result<Config, String> {
+        let cwd = env::current_dir().map_err(|x| format!("failed to read the current directory: {}", x))?;
+        Config::load_from(user_path().as_deref(), &cwd)
+    }
+
+    pub fn load_from(user: Option<&Path>, start: &Path) -> Result<Config, String> {
+        let project = start.ancestors().map(|x| x.join(FILE_NAME)).find(|x| x.is_file());
+        let mut config = Config::default();
+        for path in user.into_iter().chain(project.as_deref()) {
+            config = config.merge(Config::load_file(path)?);
+        }
+        Ok(config)
+    }
+
+    /// Loads one file; a missing file is an empty config.
+    pub fn load_file(path: &Path) -> Result<Config, String> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(ref x) if x.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(x) => return Err(format!("failed to read {}: {}", path.display(), x))
+        };
+        Config::parse(&text, path.parent().unwrap_or(Path::new(".")))
+            .map_err(|x| format!("{}: {}", path.display(), x))
+    }
+
+}
+
+pub fn user_path() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME").filter(|x| !x.is_empty()).map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".config")))
+        .or_else(|| env::var_os("APPDATA").map(PathBuf::from))
+        .map(|x| x.join("aoc").join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn parses_settings() {
+        let text = "year = 2019\ninputs = \"puzzles\"\nformat = \"json\"\ntime = true\nurl = \"http://localhost:8000\"\n";
+        let config = Config {
+            year: Some(2019),
+            inputs: Some(PathBuf::from("/work/puzzles")),
+            format: Some("json".to_string()),
+            time: Some(true),
+            url: Some("http://localhost:8000".to_string()),
+            session: None
+        };
+        assert_eq!(Ok(config), Config::parse(text, Path::new("/work")));
+        assert_eq!(Err("format must be \"text\" or \"json\"".to_string()), Config::parse("format = \"yaml\"", Path::new(".")));
+        assert!(Config::parse("year = \"2019\"", Path::new(".")).is_err());
+        assert!(Config::parse("verbose = true", Path::new(".")).is_err());
+        assert!(Config::parse("[run]\ntime = true", Path::new(".")).is_err());
+    }
+
+    #[test]
+    fn project_file_overrides_user_file() {
+        let dir = TempDir::new("config");
+        let nested = dir.join("project/src");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(dir.join("user.toml"), "year = 2018\ntime = true\nsession = \"abc\"\n").unwrap();
+        fs::write(dir.join("project").join(FILE_NAME), "year = 2019\ninputs = \"data\"\n").unwrap();
+        let config = Config::load_from(Some(&dir.join("user.toml")), &nested).unwrap();
+        assert_eq!(Some(2019), config.year);
+        assert_eq!(Some(true), config.time);
+        assert_eq!(Some("abc".to_string()), config.session);
+        assert_eq!(Some(dir.join("project/data")), config.inputs);
+        assert_eq!(Ok(Config::default()), Config::load_from(Some(&dir.join("missing.toml")), &dir));
+    }
+
+}
@@ -0,0 +1,115 @@
+//! The error type solvers return: a message plus the context it was raised
+//! in, added on the way up with [`Context`]:
+//!
+//! ```
+//! use aoc::error::{Context, Result};
+//!
+//! fn first_output(outputs: &[i64]) -> Result<i64> {
+//!     outputs.first().copied().context("the program produced no output")
+//! }
+//!
+//! let error = first_output(&[]).context("running the diagnostic").unwrap_err();
+//! assert_eq!("running the diagnostic: the program produced no output", error.to_string());
+//! ```
+
+use crate::parse;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    /// Outermost context first; the last entry is the original message.
+    chain: Vec<String>
+}
+
+impl Error {
+
+    pub fn msg<M: fmt::Display>(message: M) -> Error {
+        Error {
+            chain: vec![message.to_string()]
+        }
+    }
+
+    pub fn context<C: fmt::Display>(mut self, context: C) -> Error {
+        self.chain.insert(0, context.to_string());
+        self
+    }
+
+    /// The original message, without context.
+    pub fn root(&self) -> &str {
+        self.chain.last().unwrap()
+    }
+
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.chain.join(": "))
+    }
+}
+
+impl From<parse::Error> for Error {
+    fn from(error: parse::Error) -> Error {
+        Error::msg(error)
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::msg(message)
+    }
+}
+
+impl From<&str> for Error {
+    fn from(message: &str) -> Error {
+        Error::msg(message)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Adds context to a failed `Result`, or turns a `None` into an error.
+pub trait Context<T> {
+    fn context<C: fmt::Display>(self, context: C) -> Result<T>;
+    fn with_context<C: fmt::Display, F: FnOnce() -> C>(self, f: F) -> Result<T>;
+}
+
+impl<T, E: Into<Error>> Context<T> for std::result::Result<T, E> {
+
+    fn context<C: fmt::Display>(self, context: C) -> Result<T> {
+        self.map_err(|x| x.into().context(context))
+    }
+
+    fn with_context<C: fmt::Display, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.map_err(|x| x.into().context(f()))
+    }
+
+}
+
+impl<T> Context<T> for Option<T> {
+
+    fn context<C: fmt::Display>(self, context: C) -> Result<T> {
+        self.ok_or_else(|| Error::msg(context))
+    }
+
+    fn with_context<C: fmt::Display, F: FnOnce() -> C>(self, f: F) -> Result<T> {
+        self.ok_or_else(|| Error::msg(f()))
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn chains_context() {
+        let parsed: Result<Vec<i64>> = parse::comma_list("1,x").context("reading the program");
+        let error = parsed.with_context(|| format!("day {}", 9)).unwrap_err();
+        assert_eq!("day 9: reading the program: line 1, column 3: can't parse `x`: invalid digit found in string", error.to_string());
+        assert!(error.root().starts_with("line 1, column 3"));
+        assert_eq!(Err(Error::msg("empty")), None::<i64>.context("empty"));
+        assert_eq!(Ok(3), Some(3).context("empty"));
+    }
+
+}
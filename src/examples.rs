@@ -0,0 +1,138 @@
+//! Named example inputs from the puzzle descriptions, stored next to the real
+//! inputs with their expected answers:
+//!
+//! ```text
+//! inputs/YEAR/examples/DAY/NAME.txt    the example input
+//! inputs/YEAR/examples/DAY/NAME.toml   part1/part2, as in answers/
+//! ```
+//!
+//! `--example NAME` runs one of them; the tests below check every example
+//! against its solver. Parts without a recorded answer are not checked, since
+//! many examples only apply to one part.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub fn path(dir: &Path, year: usize, day: usize, name: &str, extension: &str) -> PathBuf {
+    dir.join(year.to_string()).join("examples").join(day.to_string()).join(format!("{}.{}", name, extension))
+}
+
+fn numbered(dir: &Path) -> Vec<(usize, PathBuf)> {
+    let mut entries: Vec<(usize, PathBuf)> = fs::read_dir(dir).into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter_map(|path| path.file_name()?.to_str()?.parse().ok().map(|number| (number, path.clone())))
+        .collect();
+    entries.sort();
+    entries
+}
+
+/// Names of the examples for each day with any, ordered by year, day and name.
+pub fn names(dir: &Path) -> Vec<(usize, usize, Vec<String>)> {
+    let mut names = Vec::new();
+    for (year, year_dir) in numbered(dir) {
+        for (day, day_dir) in numbered(&year_dir.join("examples")) {
+            let mut found: Vec<String> = fs::read_dir(&day_dir).into_iter()
+                .flatten()
+                .filter_map(|entry| entry.ok().map(|x| x.path()))
+                .filter(|path| path.extension().is_some_and(|x| x == "txt"))
+                .filter_map(|path| Some(path.file_stem()?.to_str()?.to_string()))
+                .collect();
+            found.sort();
+            if !found.is_empty() {
+                names.push((year, day, found));
+            }
+        }
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::answers::{Answers, Verdict};
+    use crate::input;
+    use crate::registry::{self, Registry};
+
+    struct Example {
+        year: usize,
+        day: usize,
+        name: String,
+        input: String,
+        answers: Answers
+    }
+
+    impl Example {
+
+        fn load(dir: &Path, year: usize, day: usize, name: &str) -> Result<Example, String> {
+            let input_path = path(dir, year, day, name, "txt");
+            let input = fs::read_to_string(&input_path)
+                .map_err(|x| format!("failed to read {}: {}", input_path.display(), x))?;
+            Ok(Example {
+                year,
+                day,
+                name: name.to_string(),
+                input,
+                answers: Answers::load_file(&path(dir, year, day, name, "toml"))?
+            })
+        }
+
+        /// Runs the example's recorded parts, returning a description of each
+        /// part that failed or disagreed with the expected answer.
+        fn check(&self, registry: &Registry) -> Vec<String> {
+            let label = format!("{}/{} {}", self.year, self.day, self.name);
+            let entry = match registry.get(&(self.year, self.day)) {
+                Some(entry) => entry,
+                None => return vec![format!("{}: no solver registered", label)]
+            };
+            let parts: Vec<usize> = (1..=2).filter(|x| self.answers.get(*x).is_some()).collect();
+            if parts.is_empty() {
+                return vec![format!("{}: no expected answers", label)];
+            }
+            (entry.solve)(&self.input, &parts, 1).parts.iter()
+                .filter_map(|run| match &run.outcome {
+                    Ok(answer) => match self.answers.check(run.part, answer) {
+                        Verdict::Wrong(expected) => Some(format!("{} part {}: expected {}, got {}", label, run.part, expected, answer)),
+                        _ => None
+                    },
+                    Err(message) => Some(format!("{} part {}: {}", label, run.part, message))
+                })
+                .collect()
+        }
+
+    }
+
+    fn discover(dir: &Path) -> Result<Vec<Example>, String> {
+        names(dir).into_iter()
+            .flat_map(|(year, day, names)| names.into_iter().map(move |name| (year, day, name)))
+            .map(|(year, day, name)| Example::load(dir, year, day, &name))
+            .collect()
+    }
+
+    #[test]
+    fn every_example_matches_its_recorded_answers() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(input::DEFAULT_DIR);
+        let examples = discover(&dir).unwrap();
+        assert!(!examples.is_empty());
+        let registry = registry::registry();
+        let failures: Vec<String> = examples.iter().flat_map(|x| x.check(&registry)).collect();
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn reports_wrong_answers() {
+        let example = Example {
+            year: 2019,
+            day: 1,
+            name: "wrong".to_string(),
+            input: "12\n".to_string(),
+            answers: Answers {
+                part1: Some("3".to_string()),
+                part2: None
+            }
+        };
+        assert_eq!(vec!["2019/1 wrong part 1: expected 3, got 2"], example.check(&registry::registry()));
+    }
+
+}
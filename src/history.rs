@@ -0,0 +1,166 @@
+//! The last-known result of every part, recorded after each run so that
+//! `aoc status` can report progress without solving anything. One
+//! `YEAR.toml` file per year:
+//!
+//! ```toml
+//! [day7]
+//! part1 = "correct"
+//! part1-ns = 4790000
+//! part1-at = 1792000000
+//! ```
+
+use crate::toml::{self, Document, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    /// Matches the recorded answer.
+    Correct,
+    Wrong,
+    /// Solved, but there's no recorded answer to check against.
+    Unchecked,
+    Unsolved,
+    Failed
+}
+
+const STATUSES: [Status; 5] = [Status::Correct, Status::Wrong, Status::Unchecked, Status::Unsolved, Status::Failed];
+
+impl Status {
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::Unchecked => "unchecked",
+            Status::Unsolved => "unsolved",
+            Status::Failed => "failed"
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    pub status: Status,
+    /// Median time of the part, if it produced an answer.
+    pub elapsed: Option<Duration>,
+    /// Seconds since the Unix epoch.
+    pub at: u64
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct History {
+    /// Keyed by `(day, part)`.
+    pub records: BTreeMap<(usize, usize), Record>
+}
+
+impl History {
+
+    pub fn load(dir: &Path, year: usize) -> Result<History, String> {
+        let path = path(dir, year);
+        let text = match fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(ref x) if x.kind() == io::ErrorKind::NotFound => return Ok(History::default()),
+            Err(x) => return Err(format!("failed to read {}: {}", path.display(), x))
+        };
+        let error = |message: String| format!("{}: {}", path.display(), message);
+        let document = toml::parse(&text).map_err(error)?;
+        let mut history = History::default();
+        for (table, entries) in &document.tables {
+            let day = table.strip_prefix("day").and_then(|x| x.parse().ok())
+                .ok_or_else(|| error(format!("unexpected table [{}]", table)))?;
+            for part in 1..=2 {
+                let key = format!("part{}", part);
+                let status = match entries.get(&key) {
+                    Some(Value::String(name)) => STATUSES.iter().copied().find(|x| x.name() == name)
+                        .ok_or_else(|| error(format!("unknown status `{}`", name)))?,
+                    Some(_) => return Err(error(format!("{}.{} must be a status", table, key))),
+                    None => continue
+                };
+                let integer = |suffix: &str| match entries.get(&format!("{}-{}", key, suffix)) {
+                    Some(Value::Integer(value)) if *value >= 0 => Some(*value as u64),
+                    _ => None
+                };
+                history.records.insert((day, part), Record {
+                    status,
+                    elapsed: integer("ns").map(Duration::from_nanos),
+                    at: integer("at").unwrap_or(0)
+                });
+            }
+        }
+        Ok(history)
+    }
+
+    pub fn save(&self, dir: &Path, year: usize) -> Result<(), String> {
+        let mut document = Document::new();
+        for ((day, part), record) in &self.records {
+            let table = format!("day{}", day);
+            let key = format!("part{}", part);
+            document.set(&table, &key, Value::String(record.status.name().to_string()));
+            if let Some(elapsed) = record.elapsed {
+                document.set(&table, &format!("{}-ns", key), Value::Integer(elapsed.as_nanos() as i64));
+            }
+            document.set(&table, &format!("{}-at", key), Value::Integer(record.at as i64));
+        }
+        let path = path(dir, year);
+        fs::create_dir_all(dir).map_err(|x| format!("failed to create {}: {}", dir.display(), x))?;
+        fs::write(&path, document.to_string()).map_err(|x| format!("failed to write {}: {}", path.display(), x))
+    }
+
+    pub fn get(&self, day: usize, part: usize) -> Option<&Record> {
+        self.records.get(&(day, part))
+    }
+
+    pub fn record(&mut self, day: usize, part: usize, record: Record) {
+        self.records.insert((day, part), record);
+    }
+
+}
+
+/// Where history is kept: `history` under the input cache directory.
+pub fn dir() -> Option<PathBuf> {
+    crate::input::cache_dir().map(|x| x.join("history"))
+}
+
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|x| x.as_secs()).unwrap_or(0)
+}
+
+fn path(dir: &Path, year: usize) -> PathBuf {
+    dir.join(format!("{}.toml", year))
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn saves_and_loads_records() {
+        let dir = TempDir::new("history");
+        assert_eq!(History::default(), History::load(&dir, 2019).unwrap());
+        let mut history = History::default();
+        history.record(7, 1, Record {
+            status: Status::Correct,
+            elapsed: Some(Duration::from_micros(4790)),
+            at: 1_792_000_000
+        });
+        history.record(6, 2, Record {
+            status: Status::Unsolved,
+            elapsed: None,
+            at: 1_792_000_000
+        });
+        history.save(&dir, 2019).unwrap();
+        let text = fs::read_to_string(dir.join("2019.toml")).unwrap();
+        assert!(text.contains("[day7]\npart1 = \"correct\"\npart1-at = 1792000000\npart1-ns = 4790000\n"), "{}", text);
+        assert_eq!(history, History::load(&dir, 2019).unwrap());
+        fs::write(dir.join("2019.toml"), "[day1]\npart1 = \"great\"\n").unwrap();
+        assert!(History::load(&dir, 2019).unwrap_err().contains("unknown status `great`"));
+    }
+
+}
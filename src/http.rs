@@ -0,0 +1,242 @@
+//! A minimal blocking HTTP/1.1 client over `TcpStream`, for talking to an
+//! AoC-compatible server. Only plain `http://` URLs are supported; anything
+//! needing TLS has to go through a local proxy.
+
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, PartialEq)]
+pub struct Url {
+    pub host: String,
+    pub port: u16,
+    /// Always starts with `/`.
+    pub path: String
+}
+
+impl Url {
+
+    pub fn parse(url: &str) -> Result<Url, String> {
+        let rest = match url.split_once("://") {
+            Some(("http", rest)) => rest,
+            Some((scheme, _)) => return Err(format!("unsupported scheme `{}` in {}, only http is supported", scheme, url)),
+            None => return Err(format!("invalid url `{}`", url))
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(index) => (&rest[..index], &rest[index..]),
+            None => (rest, "/")
+        };
+        let (host, port) = match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().map_err(|_| format!("invalid port in {}", url))?),
+            None => (authority, 80)
+        };
+        if host.is_empty() {
+            return Err(format!("missing host in {}", url));
+        }
+        Ok(Url {
+            host: host.to_string(),
+            port,
+            path: path.to_string()
+        })
+    }
+
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Response {
+    pub status: u16,
+    pub body: String
+}
+
+/// Sends a request and reads the whole response; the connection is not
+/// reused.
+pub fn request(method: &str, url: &str, headers: &[(&str, &str)], body: &str) -> Result<Response, String> {
+    let url = Url::parse(url)?;
+    let error = |x: std::io::Error| format!("{}:{}: {}", url.host, url.port, x);
+    let mut stream = TcpStream::connect((url.host.as_str(), url.port)).map_err(error)?;
+    stream.set_read_timeout(Some(TIMEOUT)).map_err(error)?;
+    stream.set_write_timeout(Some(TIMEOUT)).map_err(error)?;
+
+    let mut request = format!("{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", method, url.path, url.host);
+    request.push_str(&format!("User-Agent: aoc runner (github.com/chutchinson)\r\nContent-Length: {}\r\n", body.len()));
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    request.push_str("\r\n");
+    request.push_str(body);
+    stream.write_all(request.as_bytes()).map_err(error)?;
+
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).map_err(error)?;
+    parse_response(&response)
+}
+
+pub fn get(url: &str, headers: &[(&str, &str)]) -> Result<Response, String> {
+    request("GET", url, headers, "")
+}
+
+/// Posts `fields` as an `application/x-www-form-urlencoded` body.
+pub fn post_form(url: &str, headers: &[(&str, &str)], fields: &[(&str, &str)]) -> Result<Response, String> {
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/x-www-form-urlencoded"));
+    request("POST", url, &headers, &form_encode(fields))
+}
+
+pub fn form_encode(fields: &[(&str, &str)]) -> String {
+    let encode = |text: &str| text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b' ' => "+".to_string(),
+            b => format!("%{:02X}", b)
+        })
+        .collect::<String>();
+    fields.iter()
+        .map(|(name, value)| format!("{}={}", encode(name), encode(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+fn parse_response(response: &[u8]) -> Result<Response, String> {
+    let split = response.windows(4).position(|x| x == b"\r\n\r\n").ok_or("malformed response: no header end")?;
+    let head = String::from_utf8_lossy(&response[..split]);
+    let body = &response[split + 4..];
+    let mut lines = head.lines();
+    let status = lines.next()
+        .and_then(|x| x.split_whitespace().nth(1))
+        .and_then(|x| x.parse().ok())
+        .ok_or("malformed response: bad status line")?;
+    let header = |name: &str| lines.clone()
+        .filter_map(|x| x.split_once(':'))
+        .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.trim().to_string());
+    let body = if header("Transfer-Encoding").is_some_and(|x| x.eq_ignore_ascii_case("chunked")) {
+        dechunk(body)?
+    }
+    else if let Some(length) = header("Content-Length").and_then(|x| x.parse::<usize>().ok()) {
+        body[..length.min(body.len())].to_vec()
+    }
+    else {
+        body.to_vec()
+    };
+    Ok(Response {
+        status,
+        body: String::from_utf8_lossy(&body).into_owned()
+    })
+}
+
+fn dechunk(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut out = Vec::new();
+    loop {
+        let end = body.windows(2).position(|x| x == b"\r\n").ok_or("malformed chunked body")?;
+        let size = String::from_utf8_lossy(&body[..end]);
+        let size = usize::from_str_radix(size.split(';').next().unwrap().trim(), 16)
+            .map_err(|_| "malformed chunk size")?;
+        body = &body[end + 2..];
+        if size == 0 {
+            return Ok(out);
+        }
+        if body.len() < size {
+            return Err("truncated chunked body".to_string());
+        }
+        out.extend_from_slice(&body[..size]);
+        body = body.get(size + 2..).unwrap_or(&[]);
+    }
+}
+
+/// A one-shot local server for tests: answers each connection, in order, with
+/// the next canned response and hands back the raw requests it received.
+#[cfg(test)]
+pub mod stub {
+
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread::{self, JoinHandle};
+
+    /// Returns the server's base url and a handle yielding the requests.
+    pub fn serve(responses: Vec<(u16, &str)>) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let responses: Vec<(u16, String)> = responses.into_iter().map(|(status, body)| (status, body.to_string())).collect();
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                        length = value.trim().parse().unwrap();
+                    }
+                    request.push_str(&line);
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                let mut content = vec![0; length];
+                reader.read_exact(&mut content).unwrap();
+                request.push_str(&String::from_utf8(content).unwrap());
+                let response = format!("HTTP/1.1 {} Stub\r\nContent-Length: {}\r\n\r\n{}", status, body.len(), body);
+                reader.into_inner().write_all(response.as_bytes()).unwrap();
+                requests.push(request);
+            }
+            requests
+        });
+        (url, handle)
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parses_urls() {
+        let url = Url {
+            host: "localhost".to_string(),
+            port: 8080,
+            path: "/2019/day/1".to_string()
+        };
+        assert_eq!(Ok(url), Url::parse("http://localhost:8080/2019/day/1"));
+        assert_eq!(80, Url::parse("http://example.com").unwrap().port);
+        assert_eq!("/", Url::parse("http://example.com").unwrap().path);
+        assert!(Url::parse("https://adventofcode.com").is_err());
+        assert!(Url::parse("localhost:8080").is_err());
+        assert!(Url::parse("http://:80/").is_err());
+    }
+
+    #[test]
+    fn encodes_forms() {
+        assert_eq!("level=2&answer=a+b%26c%3D%C3%A9", form_encode(&[("level", "2"), ("answer", "a b&c=é")]));
+    }
+
+    #[test]
+    fn parses_chunked_and_sized_responses() {
+        let response = parse_response(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n5\r\nhello\r\n6;x=y\r\n world\r\n0\r\n\r\n");
+        assert_eq!(Ok(Response {
+            status: 200,
+            body: "hello world".to_string()
+        }), response);
+        let response = parse_response(b"HTTP/1.1 404 Not Found\r\ncontent-length: 3\r\n\r\nabcdef").unwrap();
+        assert_eq!((404, "abc"), (response.status, response.body.as_str()));
+        assert!(parse_response(b"HTTP/1.1 200 OK\r\n").is_err());
+    }
+
+    #[test]
+    fn talks_to_a_server() {
+        let (url, server) = stub::serve(vec![(200, "ok")]);
+        let response = post_form(&format!("{}/submit", url), &[("Cookie", "session=abc")], &[("answer", "42")]).unwrap();
+        assert_eq!((200, "ok"), (response.status, response.body.as_str()));
+        let request = &server.join().unwrap()[0];
+        assert!(request.starts_with("POST /submit HTTP/1.1\r\n"));
+        assert!(request.contains("Cookie: session=abc\r\n"));
+        assert!(request.ends_with("\r\n\r\nanswer=42"));
+    }
+
+}
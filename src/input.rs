@@ -0,0 +1,169 @@
+//! Locating and reading puzzle inputs at runtime, and fetching them into a
+//! per-user cache so they needn't be committed.
+
+use crate::client::Client;
+use crate::examples;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_DIR: &str = "inputs";
+
+/// Where downloaded inputs are kept: `$XDG_CACHE_HOME/aoc`, `~/.cache/aoc`,
+/// or `%LOCALAPPDATA%\aoc` on Windows.
+pub fn cache_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CACHE_HOME").filter(|x| !x.is_empty()).map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|x| PathBuf::from(x).join(".cache")))
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))
+        .map(|x| x.join("aoc"))
+}
+
+fn day_path(dir: &Path, year: usize, day: usize) -> PathBuf {
+    dir.join(year.to_string()).join(format!("{}.txt", day))
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Downloaded(PathBuf),
+    Cached(PathBuf)
+}
+
+/// Downloads a day's input into `cache`, unless it's already there.
+pub fn fetch(client: &Client, cache: &Path, year: usize, day: usize) -> Result<Fetched, String> {
+    let path = day_path(cache, year, day);
+    if path.exists() {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client.input(year, day)?;
+    if input.is_empty() {
+        return Err(format!("the input for {} day {} is empty", year, day));
+    }
+    let parent = path.parent().unwrap();
+    fs::create_dir_all(parent).map_err(|x| format!("failed to create {}: {}", parent.display(), x))?;
+    fs::write(&path, input).map_err(|x| format!("failed to write {}: {}", path.display(), x))?;
+    Ok(Fetched::Downloaded(path))
+}
+
+/// Where puzzle inputs are read from at runtime.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A directory holding a `YEAR/DAY.txt` file per day.
+    Directory(PathBuf),
+    /// A single file, for running one day against someone else's input.
+    File(PathBuf),
+    Stdin,
+    /// A named example under `DIR/YEAR/examples/DAY/`; see `examples`.
+    Example {
+        dir: PathBuf,
+        name: String
+    }
+}
+
+impl Default for Source {
+    fn default() -> Source {
+        Source::Directory(PathBuf::from(DEFAULT_DIR))
+    }
+}
+
+impl Source {
+
+    /// Where a fetched input for the day would be cached, if this source falls
+    /// back to the cache: only the default inputs directory does, so that an
+    /// explicit `--inputs DIR` is read as given.
+    pub fn cache_path(&self, year: usize, day: usize) -> Option<PathBuf> {
+        match self {
+            Source::Directory(dir) if dir == Path::new(DEFAULT_DIR) => cache_dir().map(|x| day_path(&x, year, day)),
+            _ => None
+        }
+    }
+
+    pub fn load(&self, year: usize, day: usize) -> Result<String, String> {
+        match self {
+            Source::Directory(dir) => {
+                let path = day_path(dir, year, day);
+                let cached = self.cache_path(year, day);
+                if let Some(cached) = cached.as_ref().filter(|x| !path.exists() && x.exists()) {
+                    return read(cached);
+                }
+                let hint = match cached {
+                    Some(_) => format!("run `aoc fetch {}`, or pass --input FILE or --inputs DIR", day),
+                    None => "pass --input FILE or another --inputs DIR".to_string()
+                };
+                read(&path).map_err(|x| format!("{}; {}", x, hint))
+            },
+            Source::File(path) => read(path),
+            Source::Example { dir, name } => read(&examples::path(dir, year, day, name, "txt"))
+                .map_err(|x| format!("{}; see `aoc list` for examples", x)),
+            Source::Stdin => {
+                let mut input = String::new();
+                io::stdin().read_to_string(&mut input)
+                    .map_err(|x| format!("failed to read input from stdin: {}", x))?;
+                Ok(input)
+            }
+        }
+    }
+
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|x| match x.kind() {
+        io::ErrorKind::NotFound => format!("input file {} not found", path.display()),
+        _ => format!("failed to read input file {}: {}", path.display(), x)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::http::stub;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn loads_inputs_from_directory_and_file() {
+        let dir = TempDir::new("input-load");
+        fs::create_dir_all(dir.join("2019")).unwrap();
+        fs::write(dir.join("2019/3.txt"), "R8,U5").unwrap();
+        assert_eq!(Ok("R8,U5".to_string()), Source::Directory(dir.to_path_buf()).load(2019, 3));
+        assert_eq!(Ok("R8,U5".to_string()), Source::File(dir.join("2019/3.txt")).load(2019, 9));
+    }
+
+    #[test]
+    fn reports_missing_files() {
+        // No puzzle has a day 26, so nothing is cached for it either.
+        let error = Source::default().load(2019, 26).unwrap_err();
+        assert!(error.contains("2019/26.txt not found"), "{}", error);
+        assert!(error.contains("aoc fetch 26"), "{}", error);
+        assert!(error.contains("--input FILE"), "{}", error);
+        let dir = TempDir::new("input-missing");
+        let error = Source::Directory(dir.to_path_buf()).load(2019, 26).unwrap_err();
+        assert!(error.contains("2019/26.txt not found"), "{}", error);
+        assert!(!error.contains("aoc fetch"), "{}", error);
+    }
+
+    #[test]
+    fn only_the_default_directory_falls_back_to_the_cache() {
+        assert_eq!(cache_dir().map(|x| x.join("2019/3.txt")), Source::default().cache_path(2019, 3));
+        assert_eq!(None, Source::Directory(PathBuf::from("theirs")).cache_path(2019, 3));
+        assert_eq!(None, Source::File(PathBuf::from("inputs/2019/3.txt")).cache_path(2019, 3));
+    }
+
+    #[test]
+    fn fetches_each_input_once() {
+        let (url, server) = stub::serve(vec![(200, "1,2,3\n"), (404, "not yet")]);
+        let cache = TempDir::new("input-cache");
+        let client = Client::new(&url, "secret");
+        let path = cache.join("2019/2.txt");
+        assert_eq!(Ok(Fetched::Downloaded(path.clone())), fetch(&client, &cache, 2019, 2));
+        assert_eq!(Ok(Fetched::Cached(path.clone())), fetch(&client, &cache, 2019, 2));
+        assert_eq!("1,2,3\n", fs::read_to_string(&path).unwrap());
+        assert!(fetch(&client, &cache, 2019, 25).is_err());
+        assert!(!cache.join("2019/25.txt").exists());
+        let requests = server.join().unwrap();
+        assert_eq!(2, requests.len());
+        assert!(requests[0].starts_with("GET /2019/day/2/input HTTP/1.1\r\n"));
+        assert!(requests[0].contains("Cookie: session=secret\r\n"));
+    }
+
+}
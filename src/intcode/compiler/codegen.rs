@@ -0,0 +1,356 @@
+use super::parser::{BinaryOp, Expr, Function, Stmt, UnaryOp};
+use super::{CompileError, Position};
+use std::collections::HashMap;
+
+const OP_ADD: i64 = 1;
+const OP_MUL: i64 = 2;
+const OP_INPUT: i64 = 3;
+const OP_OUTPUT: i64 = 4;
+const OP_JT: i64 = 5;
+const OP_JF: i64 = 6;
+const OP_CLT: i64 = 7;
+const OP_CEQ: i64 = 8;
+const OP_RBO: i64 = 9;
+const OP_HALT: i64 = 99;
+
+// Frame layout, relative to the base: return address, return value, params,
+// locals, then expression temporaries. A callee's frame starts right after
+// the caller's, so calls address it with `Callee` slots and bump the base by
+// the caller's frame size, which is only known once the function is done.
+const RETURN_ADDRESS: i64 = 0;
+const RETURN_VALUE: i64 = 1;
+const FIRST_PARAM: i64 = 2;
+
+#[derive(Debug, Copy, Clone)]
+enum Item {
+    Value(i64),
+    Label(usize),
+    Callee(i64),
+    FrameSize(i64)
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Operand {
+    Immediate(i64),
+    Label(usize),
+    /// Slot in the current frame.
+    Slot(i64),
+    /// Slot in the frame of the function about to be called.
+    Callee(i64),
+    /// Current frame size multiplied by the given sign.
+    FrameSize(i64)
+}
+
+impl Operand {
+
+    fn mode(&self) -> i64 {
+        match self {
+            Operand::Slot(_) | Operand::Callee(_) => 2,
+            _ => 1
+        }
+    }
+
+    fn item(&self) -> Item {
+        match *self {
+            Operand::Immediate(value) | Operand::Slot(value) => Item::Value(value),
+            Operand::Label(label) => Item::Label(label),
+            Operand::Callee(slot) => Item::Callee(slot),
+            Operand::FrameSize(sign) => Item::FrameSize(sign)
+        }
+    }
+
+}
+
+struct Generator {
+    code: Vec<Item>,
+    labels: Vec<Option<usize>>,
+    functions: HashMap<String, (usize, usize)>,
+    scopes: Vec<HashMap<String, i64>>,
+    next_slot: i64,
+    next_temp: i64,
+    frame_size: i64
+}
+
+pub fn generate(functions: &[Function]) -> Result<Vec<i64>, CompileError> {
+    let mut generator = Generator {
+        code: Vec::new(),
+        labels: Vec::new(),
+        functions: HashMap::new(),
+        scopes: Vec::new(),
+        next_slot: 0,
+        next_temp: 0,
+        frame_size: 0
+    };
+
+    for function in functions {
+        if function.name == "input" || function.name == "output" {
+            return Err(CompileError::new(function.position, format!("`{}` is a builtin", function.name)));
+        }
+        let label = generator.label();
+        let previous = generator.functions.insert(function.name.clone(), (label, function.params.len()));
+        if previous.is_some() {
+            return Err(CompileError::new(function.position, format!("function `{}` is already defined", function.name)));
+        }
+    }
+
+    let main = match functions.iter().find(|x| x.name == "main") {
+        Some(main) => main,
+        None => return Err(CompileError::new(Position { line: 1, column: 1 }, "missing `main` function".to_string()))
+    };
+    if !main.params.is_empty() {
+        return Err(CompileError::new(main.position, "`main` cannot take parameters".to_string()));
+    }
+
+    // Point the base at the end of the image, call main and halt on return.
+    let end = generator.label();
+    let halt = generator.label();
+    generator.emit(OP_RBO, &[Operand::Label(end)]);
+    generator.emit(OP_ADD, &[Operand::Label(halt), Operand::Immediate(0), Operand::Slot(RETURN_ADDRESS)]);
+    generator.emit(OP_JT, &[Operand::Immediate(1), Operand::Label(generator.functions["main"].0)]);
+    generator.bind(halt);
+    generator.emit(OP_HALT, &[]);
+
+    for function in functions {
+        generator.function(function)?;
+    }
+    generator.bind(end);
+
+    let labels = &generator.labels;
+    Ok(generator.code.iter()
+        .map(|item| match *item {
+            Item::Value(value) => value,
+            Item::Label(label) => labels[label].unwrap() as i64,
+            _ => unreachable!("frame items are resolved per function")
+        })
+        .collect())
+}
+
+impl Generator {
+
+    fn label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn bind(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+    }
+
+    fn emit(&mut self, opcode: i64, operands: &[Operand]) {
+        let mut instruction = opcode;
+        let mut scale = 100;
+        for operand in operands {
+            instruction += operand.mode() * scale;
+            scale *= 10;
+        }
+        self.code.push(Item::Value(instruction));
+        self.code.extend(operands.iter().map(|x| x.item()));
+    }
+
+    fn copy(&mut self, from: Operand, to: Operand) {
+        self.emit(OP_ADD, &[from, Operand::Immediate(0), to]);
+    }
+
+    fn temp(&mut self) -> Operand {
+        let slot = self.next_temp;
+        self.next_temp += 1;
+        self.frame_size = std::cmp::max(self.frame_size, self.next_temp);
+        Operand::Slot(slot)
+    }
+
+    fn function(&mut self, function: &Function) -> Result<(), CompileError> {
+        let start = self.code.len();
+        let mut params = HashMap::new();
+        for (index, name) in function.params.iter().enumerate() {
+            if params.insert(name.clone(), FIRST_PARAM + index as i64).is_some() {
+                return Err(CompileError::new(function.position, format!("duplicate parameter `{}`", name)));
+            }
+        }
+        self.scopes = vec![params];
+        self.next_slot = FIRST_PARAM + function.params.len() as i64;
+        self.frame_size = self.next_slot;
+
+        let label = self.functions[&function.name].0;
+        self.bind(label);
+        self.block(&function.body)?;
+        self.ret(Operand::Immediate(0));
+
+        let frame_size = self.frame_size;
+        for item in self.code[start..].iter_mut() {
+            match *item {
+                Item::Callee(slot) => *item = Item::Value(frame_size + slot),
+                Item::FrameSize(sign) => *item = Item::Value(sign * frame_size),
+                _ => ()
+            }
+        }
+        Ok(())
+    }
+
+    fn ret(&mut self, value: Operand) {
+        self.copy(value, Operand::Slot(RETURN_VALUE));
+        self.emit(OP_JT, &[Operand::Immediate(1), Operand::Slot(RETURN_ADDRESS)]);
+    }
+
+    fn block(&mut self, statements: &[Stmt]) -> Result<(), CompileError> {
+        self.scopes.push(HashMap::new());
+        for statement in statements {
+            self.next_temp = self.next_slot;
+            self.statement(statement)?;
+        }
+        self.scopes.pop();
+        Ok(())
+    }
+
+    fn lookup(&self, name: &str, position: Position) -> Result<Operand, CompileError> {
+        self.scopes.iter().rev()
+            .find_map(|scope| scope.get(name))
+            .map(|slot| Operand::Slot(*slot))
+            .ok_or_else(|| CompileError::new(position, format!("undefined variable `{}`", name)))
+    }
+
+    fn statement(&mut self, statement: &Stmt) -> Result<(), CompileError> {
+        match statement {
+            Stmt::Let(name, value, position) => {
+                let value = self.expr(value)?;
+                if self.scopes.last().unwrap().contains_key(name) {
+                    return Err(CompileError::new(*position, format!("variable `{}` is already defined", name)));
+                }
+                let slot = self.next_slot;
+                self.next_slot += 1;
+                self.frame_size = std::cmp::max(self.frame_size, self.next_slot);
+                self.scopes.last_mut().unwrap().insert(name.clone(), slot);
+                self.copy(value, Operand::Slot(slot));
+            },
+            Stmt::Assign(name, value, position) => {
+                let target = self.lookup(name, *position)?;
+                let value = self.expr(value)?;
+                self.copy(value, target);
+            },
+            Stmt::If(condition, then, otherwise) => {
+                let condition = self.expr(condition)?;
+                let skip = self.label();
+                let end = self.label();
+                self.emit(OP_JF, &[condition, Operand::Label(skip)]);
+                self.block(then)?;
+                self.emit(OP_JT, &[Operand::Immediate(1), Operand::Label(end)]);
+                self.bind(skip);
+                self.block(otherwise)?;
+                self.bind(end);
+            },
+            Stmt::While(condition, body) => {
+                let top = self.label();
+                let end = self.label();
+                self.bind(top);
+                let condition = self.expr(condition)?;
+                self.emit(OP_JF, &[condition, Operand::Label(end)]);
+                self.block(body)?;
+                self.emit(OP_JT, &[Operand::Immediate(1), Operand::Label(top)]);
+                self.bind(end);
+            },
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expr(value)?,
+                    None => Operand::Immediate(0)
+                };
+                self.ret(value);
+            },
+            Stmt::Expr(value) => {
+                self.expr(value)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &Expr) -> Result<Operand, CompileError> {
+        let operand = match expr {
+            Expr::Number(value) => Operand::Immediate(*value),
+            Expr::Var(name, position) => self.lookup(name, *position)?,
+            Expr::Call(name, args, position) => self.call(name, args, *position)?,
+            Expr::Unary(op, value) => {
+                let value = self.expr(value)?;
+                let dest = self.temp();
+                match op {
+                    UnaryOp::Negate => self.emit(OP_MUL, &[value, Operand::Immediate(-1), dest]),
+                    UnaryOp::Not => self.emit(OP_CEQ, &[value, Operand::Immediate(0), dest])
+                }
+                dest
+            },
+            Expr::Binary(op, left, right) => {
+                let a = self.expr(left)?;
+                let b = self.expr(right)?;
+                let dest = self.temp();
+                match op {
+                    BinaryOp::Add => self.emit(OP_ADD, &[a, b, dest]),
+                    BinaryOp::Mul => self.emit(OP_MUL, &[a, b, dest]),
+                    BinaryOp::Sub => {
+                        self.emit(OP_MUL, &[b, Operand::Immediate(-1), dest]);
+                        self.emit(OP_ADD, &[a, dest, dest]);
+                    },
+                    BinaryOp::Less => self.emit(OP_CLT, &[a, b, dest]),
+                    BinaryOp::Greater => self.emit(OP_CLT, &[b, a, dest]),
+                    BinaryOp::Equal => self.emit(OP_CEQ, &[a, b, dest]),
+                    BinaryOp::LessEqual | BinaryOp::GreaterEqual | BinaryOp::NotEqual => {
+                        match op {
+                            BinaryOp::LessEqual => self.emit(OP_CLT, &[b, a, dest]),
+                            BinaryOp::GreaterEqual => self.emit(OP_CLT, &[a, b, dest]),
+                            _ => self.emit(OP_CEQ, &[a, b, dest])
+                        }
+                        self.emit(OP_CEQ, &[dest, Operand::Immediate(0), dest]);
+                    }
+                }
+                dest
+            }
+        };
+        Ok(operand)
+    }
+
+    fn call(&mut self, name: &str, args: &[Expr], position: Position) -> Result<Operand, CompileError> {
+        let arity = match name {
+            "input" => 0,
+            "output" => 1,
+            _ => match self.functions.get(name) {
+                Some(function) => function.1,
+                None => return Err(CompileError::new(position, format!("unknown function `{}`", name)))
+            }
+        };
+        if args.len() != arity {
+            let message = format!("`{}` takes {} argument(s) but {} were given", name, arity, args.len());
+            return Err(CompileError::new(position, message));
+        }
+
+        // Evaluate every argument before writing any of them, since a nested
+        // call would overwrite the callee frame.
+        let mut values = Vec::with_capacity(args.len());
+        for arg in args {
+            values.push(self.expr(arg)?);
+        }
+
+        match name {
+            "input" => {
+                let dest = self.temp();
+                self.emit(OP_INPUT, &[dest]);
+                Ok(dest)
+            },
+            "output" => {
+                self.emit(OP_OUTPUT, &[values[0]]);
+                Ok(values[0])
+            },
+            _ => {
+                let function = self.functions[name].0;
+                let resume = self.label();
+                for (index, value) in values.into_iter().enumerate() {
+                    self.copy(value, Operand::Callee(FIRST_PARAM + index as i64));
+                }
+                self.copy(Operand::Label(resume), Operand::Callee(RETURN_ADDRESS));
+                self.emit(OP_RBO, &[Operand::FrameSize(1)]);
+                self.emit(OP_JT, &[Operand::Immediate(1), Operand::Label(function)]);
+                self.bind(resume);
+                self.emit(OP_RBO, &[Operand::FrameSize(-1)]);
+                let dest = self.temp();
+                self.copy(Operand::Callee(RETURN_VALUE), dest);
+                Ok(dest)
+            }
+        }
+    }
+
+}
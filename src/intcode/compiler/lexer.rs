@@ -0,0 +1,154 @@
+use super::{CompileError, Position};
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Token {
+    Number(i64),
+    Ident(String),
+    Fn,
+    Let,
+    If,
+    Else,
+    While,
+    Return,
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    Comma,
+    Semicolon,
+    Assign,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Plus,
+    Minus,
+    Star,
+    Bang,
+    Eof
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Token::Number(value) => return write!(f, "`{}`", value),
+            Token::Ident(name) => return write!(f, "`{}`", name),
+            Token::Eof => return write!(f, "end of input"),
+            Token::Fn => "fn",
+            Token::Let => "let",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::While => "while",
+            Token::Return => "return",
+            Token::LeftParen => "(",
+            Token::RightParen => ")",
+            Token::LeftBrace => "{",
+            Token::RightBrace => "}",
+            Token::Comma => ",",
+            Token::Semicolon => ";",
+            Token::Assign => "=",
+            Token::Equal => "==",
+            Token::NotEqual => "!=",
+            Token::Less => "<",
+            Token::LessEqual => "<=",
+            Token::Greater => ">",
+            Token::GreaterEqual => ">=",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Bang => "!"
+        };
+        write!(f, "`{}`", text)
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Position)>, CompileError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut index = 0;
+    let mut line = 1;
+    let mut column = 1;
+
+    while index < chars.len() {
+        let c = chars[index];
+        let position = Position { line, column };
+        let next = chars.get(index + 1).copied();
+
+        if c == '\n' {
+            index += 1;
+            line += 1;
+            column = 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            index += 1;
+            column += 1;
+            continue;
+        }
+        if c == '/' && next == Some('/') {
+            while index < chars.len() && chars[index] != '\n' {
+                index += 1;
+            }
+            continue;
+        }
+
+        let start = index;
+        let token = if c.is_ascii_digit() {
+            while index < chars.len() && chars[index].is_ascii_digit() {
+                index += 1;
+            }
+            let text: String = chars[start..index].iter().collect();
+            let value = text.parse::<i64>()
+                .map_err(|_| CompileError::new(position, format!("number `{}` is too large", text)))?;
+            Token::Number(value)
+        }
+        else if c.is_ascii_alphabetic() || c == '_' {
+            while index < chars.len() && (chars[index].is_ascii_alphanumeric() || chars[index] == '_') {
+                index += 1;
+            }
+            let text: String = chars[start..index].iter().collect();
+            match text.as_str() {
+                "fn" => Token::Fn,
+                "let" => Token::Let,
+                "if" => Token::If,
+                "else" => Token::Else,
+                "while" => Token::While,
+                "return" => Token::Return,
+                _ => Token::Ident(text)
+            }
+        }
+        else {
+            let (token, len) = match (c, next) {
+                ('=', Some('=')) => (Token::Equal, 2),
+                ('!', Some('=')) => (Token::NotEqual, 2),
+                ('<', Some('=')) => (Token::LessEqual, 2),
+                ('>', Some('=')) => (Token::GreaterEqual, 2),
+                ('=', _) => (Token::Assign, 1),
+                ('!', _) => (Token::Bang, 1),
+                ('<', _) => (Token::Less, 1),
+                ('>', _) => (Token::Greater, 1),
+                ('(', _) => (Token::LeftParen, 1),
+                (')', _) => (Token::RightParen, 1),
+                ('{', _) => (Token::LeftBrace, 1),
+                ('}', _) => (Token::RightBrace, 1),
+                (',', _) => (Token::Comma, 1),
+                (';', _) => (Token::Semicolon, 1),
+                ('+', _) => (Token::Plus, 1),
+                ('-', _) => (Token::Minus, 1),
+                ('*', _) => (Token::Star, 1),
+                _ => return Err(CompileError::new(position, format!("unexpected character `{}`", c)))
+            };
+            index += len;
+            token
+        };
+
+        column += index - start;
+        tokens.push((token, position));
+    }
+
+    tokens.push((Token::Eof, Position { line, column }));
+    Ok(tokens)
+}
@@ -0,0 +1,199 @@
+//! Compiler for a tiny C-like language targeting Intcode.
+//!
+//! ```text
+//! fn sum(n) {
+//!     let total = 0;
+//!     while (n > 0) {
+//!         total = total + n;
+//!         n = n - 1;
+//!     }
+//!     return total;
+//! }
+//!
+//! fn main() {
+//!     output(sum(input()));
+//! }
+//! ```
+//!
+//! Values are integers. Supported are `let`, assignment, `if`/`else`, `while`,
+//! `return`, function calls (including recursion), `+ - *`, comparisons
+//! (`< <= > >= == !=`), unary `-` and `!`, and the builtins `input()` and
+//! `output(x)`. Variables are block scoped and functions may be called before
+//! they are defined. Execution starts at `main`.
+//!
+//! Each call gets a frame addressed through the relative base, which acts as
+//! the stack pointer; the stack starts at the end of the program image, so the
+//! program must be run with enough extra memory for its deepest call chain.
+
+mod codegen;
+mod lexer;
+mod parser;
+
+use std::fmt;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompileError {
+    pub position: Position,
+    pub message: String
+}
+
+impl CompileError {
+
+    fn new(position: Position, message: String) -> CompileError {
+        CompileError {
+            position,
+            message
+        }
+    }
+
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.position.line, self.position.column, self.message)
+    }
+}
+
+pub fn compile(source: &str) -> Result<Vec<i64>, CompileError> {
+    let tokens = lexer::tokenize(source)?;
+    let functions = parser::parse(tokens)?;
+    codegen::generate(&functions)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::intcode::IntcodeBuilder;
+
+    fn execute(source: &str, inputs: &[i64]) -> Vec<i64> {
+        let program = compile(source).unwrap();
+        let mut vm = IntcodeBuilder::new()
+            .with_memory_size(program.len() + 4096)
+            .with_memory(&program)
+            .with_inputs(inputs)
+            .build();
+        vm.run();
+        vm.outputs
+    }
+
+    fn error(source: &str) -> String {
+        compile(source).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn evaluates_expressions() {
+        let source = "fn main() { output(1 + 2 * 3); output(-(4 - 10)); output(2 * 3 - 4 * 5); }";
+        assert_eq!(vec![7, 6, -14], execute(source, &[]));
+    }
+
+    #[test]
+    fn evaluates_comparisons() {
+        let source = "
+            fn main() {
+                let a = input();
+                let b = input();
+                output(a < b); output(a <= b); output(a > b);
+                output(a >= b); output(a == b); output(a != b); output(!a);
+            }";
+        assert_eq!(vec![1, 1, 0, 0, 0, 1, 1], execute(source, &[0, 5]));
+        assert_eq!(vec![0, 1, 0, 1, 1, 0, 0], execute(source, &[5, 5]));
+        assert_eq!(vec![0, 0, 1, 1, 0, 1, 0], execute(source, &[7, 5]));
+    }
+
+    #[test]
+    fn runs_loops_and_branches() {
+        let source = "
+            fn main() {
+                let n = input();
+                let total = 0;
+                while (n > 0) {
+                    if (n == 3) {
+                        total = total + 100;
+                    } else if (n < 3) {
+                        total = total + 1;
+                    } else {
+                        let doubled = n * 2;
+                        total = total + doubled;
+                    }
+                    n = n - 1;
+                }
+                output(total);
+            }";
+        assert_eq!(vec![100 + 1 + 1 + 10 + 8], execute(source, &[5]));
+    }
+
+    #[test]
+    fn supports_recursion() {
+        let source = "
+            fn fib(n) {
+                if (n < 2) { return n; }
+                return fib(n - 1) + fib(n - 2);
+            }
+            fn factorial(n) {
+                if (n == 0) { return 1; }
+                return n * factorial(n - 1);
+            }
+            fn main() {
+                output(fib(input()));
+                output(factorial(input()));
+            }";
+        assert_eq!(vec![610, 3628800], execute(source, &[15, 10]));
+    }
+
+    #[test]
+    fn evaluates_nested_call_arguments() {
+        let source = "
+            fn pair(a, b) { return a * 100 + b; }
+            fn id(x) { return x; }
+            fn main() { output(pair(id(1), pair(id(2), 3))); }";
+        assert_eq!(vec![100 + 203], execute(source, &[]));
+    }
+
+    #[test]
+    fn counts_primes() {
+        let source = "
+            // no division, so remainder is repeated subtraction
+            fn rem(a, b) {
+                while (a >= b) { a = a - b; }
+                return a;
+            }
+            fn is_prime(n) {
+                if (n < 2) { return 0; }
+                let d = 2;
+                while (d * d <= n) {
+                    if (rem(n, d) == 0) { return 0; }
+                    d = d + 1;
+                }
+                return 1;
+            }
+            fn main() {
+                let count = 0;
+                let n = 0;
+                while (n < 100) {
+                    count = count + is_prime(n);
+                    n = n + 1;
+                }
+                output(count);
+            }";
+        assert_eq!(vec![25], execute(source, &[]));
+    }
+
+    #[test]
+    fn reports_errors_with_positions() {
+        assert_eq!("1:28: undefined variable `y`", error("fn main() { let x = 1; x = y; }"));
+        assert_eq!("1:13: unknown function `f`", error("fn main() { f(); }"));
+        assert_eq!("1:25: `f` takes 1 argument(s) but 2 were given", error("fn f(a) { } fn main() { f(1, 2); }"));
+        assert_eq!("1:1: missing `main` function", error("fn f() { }"));
+        assert_eq!("1:23: expected `;`, found `}`", error("fn main() { output(1) }"));
+        assert_eq!("2:5: unexpected character `$`", error("fn main() {\n    $ }"));
+        assert_eq!("1:28: variable `x` is already defined", error("fn main() { let x = 1; let x = 2; }"));
+    }
+
+}
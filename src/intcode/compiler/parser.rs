@@ -0,0 +1,298 @@
+use super::lexer::Token;
+use super::{CompileError, Position};
+
+#[derive(Debug)]
+pub struct Function {
+    pub name: String,
+    pub params: Vec<String>,
+    pub body: Vec<Stmt>,
+    pub position: Position
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+    Let(String, Expr, Position),
+    Assign(String, Expr, Position),
+    If(Expr, Vec<Stmt>, Vec<Stmt>),
+    While(Expr, Vec<Stmt>),
+    Return(Option<Expr>),
+    Expr(Expr)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum UnaryOp {
+    Negate,
+    Not
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Equal,
+    NotEqual
+}
+
+#[derive(Debug)]
+pub enum Expr {
+    Number(i64),
+    Var(String, Position),
+    Call(String, Vec<Expr>, Position),
+    Unary(UnaryOp, Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>)
+}
+
+struct Parser {
+    tokens: Vec<(Token, Position)>,
+    index: usize
+}
+
+pub fn parse(tokens: Vec<(Token, Position)>) -> Result<Vec<Function>, CompileError> {
+    let mut parser = Parser {
+        tokens,
+        index: 0
+    };
+    let mut functions = Vec::new();
+    while *parser.peek() != Token::Eof {
+        functions.push(parser.function()?);
+    }
+    Ok(functions)
+}
+
+impl Parser {
+
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn position(&self) -> Position {
+        self.tokens[self.index].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if token != Token::Eof {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn accept(&mut self, token: Token) -> bool {
+        if *self.peek() == token {
+            self.advance();
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), CompileError> {
+        if self.accept(token) {
+            return Ok(());
+        }
+        Err(self.unexpected(what))
+    }
+
+    fn unexpected(&self, what: &str) -> CompileError {
+        CompileError::new(self.position(), format!("expected {}, found {}", what, self.peek()))
+    }
+
+    fn ident(&mut self) -> Result<(String, Position), CompileError> {
+        let position = self.position();
+        match self.peek().clone() {
+            Token::Ident(name) => {
+                self.advance();
+                Ok((name, position))
+            },
+            _ => Err(self.unexpected("identifier"))
+        }
+    }
+
+    fn function(&mut self) -> Result<Function, CompileError> {
+        self.expect(Token::Fn, "`fn`")?;
+        let (name, position) = self.ident()?;
+        self.expect(Token::LeftParen, "`(`")?;
+        let mut params = Vec::new();
+        if !self.accept(Token::RightParen) {
+            loop {
+                params.push(self.ident()?.0);
+                if self.accept(Token::RightParen) {
+                    break;
+                }
+                self.expect(Token::Comma, "`,` or `)`")?;
+            }
+        }
+        let body = self.block()?;
+        Ok(Function {
+            name,
+            params,
+            body,
+            position
+        })
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        self.expect(Token::LeftBrace, "`{`")?;
+        let mut statements = Vec::new();
+        while !self.accept(Token::RightBrace) {
+            statements.push(self.statement()?);
+        }
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, CompileError> {
+        match self.peek() {
+            Token::Let => {
+                self.advance();
+                let (name, position) = self.ident()?;
+                self.expect(Token::Assign, "`=`")?;
+                let value = self.expr()?;
+                self.expect(Token::Semicolon, "`;`")?;
+                Ok(Stmt::Let(name, value, position))
+            },
+            Token::If => {
+                self.advance();
+                let condition = self.condition()?;
+                let then = self.block()?;
+                let otherwise = if !self.accept(Token::Else) {
+                    Vec::new()
+                }
+                else if *self.peek() == Token::If {
+                    vec![self.statement()?]
+                }
+                else {
+                    self.block()?
+                };
+                Ok(Stmt::If(condition, then, otherwise))
+            },
+            Token::While => {
+                self.advance();
+                let condition = self.condition()?;
+                let body = self.block()?;
+                Ok(Stmt::While(condition, body))
+            },
+            Token::Return => {
+                self.advance();
+                let value = if *self.peek() == Token::Semicolon { None } else { Some(self.expr()?) };
+                self.expect(Token::Semicolon, "`;`")?;
+                Ok(Stmt::Return(value))
+            },
+            Token::Ident(_) if self.tokens[self.index + 1].0 == Token::Assign => {
+                let (name, position) = self.ident()?;
+                self.advance();
+                let value = self.expr()?;
+                self.expect(Token::Semicolon, "`;`")?;
+                Ok(Stmt::Assign(name, value, position))
+            },
+            _ => {
+                let value = self.expr()?;
+                self.expect(Token::Semicolon, "`;`")?;
+                Ok(Stmt::Expr(value))
+            }
+        }
+    }
+
+    fn condition(&mut self) -> Result<Expr, CompileError> {
+        self.expect(Token::LeftParen, "`(`")?;
+        let condition = self.expr()?;
+        self.expect(Token::RightParen, "`)`")?;
+        Ok(condition)
+    }
+
+    fn expr(&mut self) -> Result<Expr, CompileError> {
+        self.equality()
+    }
+
+    fn binary<F>(&mut self, operand: F, operators: &[(Token, BinaryOp)]) -> Result<Expr, CompileError>
+        where F: Fn(&mut Parser) -> Result<Expr, CompileError> {
+        let mut left = operand(self)?;
+        'outer: loop {
+            for (token, op) in operators {
+                if self.accept(token.clone()) {
+                    let right = operand(self)?;
+                    left = Expr::Binary(*op, Box::new(left), Box::new(right));
+                    continue 'outer;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn equality(&mut self) -> Result<Expr, CompileError> {
+        self.binary(Parser::comparison, &[
+            (Token::Equal, BinaryOp::Equal),
+            (Token::NotEqual, BinaryOp::NotEqual)
+        ])
+    }
+
+    fn comparison(&mut self) -> Result<Expr, CompileError> {
+        self.binary(Parser::additive, &[
+            (Token::Less, BinaryOp::Less),
+            (Token::LessEqual, BinaryOp::LessEqual),
+            (Token::Greater, BinaryOp::Greater),
+            (Token::GreaterEqual, BinaryOp::GreaterEqual)
+        ])
+    }
+
+    fn additive(&mut self) -> Result<Expr, CompileError> {
+        self.binary(Parser::term, &[
+            (Token::Plus, BinaryOp::Add),
+            (Token::Minus, BinaryOp::Sub)
+        ])
+    }
+
+    fn term(&mut self) -> Result<Expr, CompileError> {
+        self.binary(Parser::unary, &[
+            (Token::Star, BinaryOp::Mul)
+        ])
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        if self.accept(Token::Minus) {
+            return Ok(Expr::Unary(UnaryOp::Negate, Box::new(self.unary()?)));
+        }
+        if self.accept(Token::Bang) {
+            return Ok(Expr::Unary(UnaryOp::Not, Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let position = self.position();
+        match self.peek().clone() {
+            Token::Number(value) => {
+                self.advance();
+                Ok(Expr::Number(value))
+            },
+            Token::LeftParen => {
+                self.advance();
+                let value = self.expr()?;
+                self.expect(Token::RightParen, "`)`")?;
+                Ok(value)
+            },
+            Token::Ident(name) => {
+                self.advance();
+                if !self.accept(Token::LeftParen) {
+                    return Ok(Expr::Var(name, position));
+                }
+                let mut args = Vec::new();
+                if !self.accept(Token::RightParen) {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.accept(Token::RightParen) {
+                            break;
+                        }
+                        self.expect(Token::Comma, "`,` or `)`")?;
+                    }
+                }
+                Ok(Expr::Call(name, args, position))
+            },
+            _ => Err(self.unexpected("expression"))
+        }
+    }
+
+}
@@ -0,0 +1,215 @@
+//! Data-driven conformance suite for Intcode engines.
+//!
+//! Each file in `src/intcode/cases` describes one program run as `key: value`
+//! lines (`#` starts a comment):
+//!
+//! - `program`: comma-separated program image (required)
+//! - `memory-size`: total memory to allocate before running
+//! - `inputs`: comma-separated input queue
+//! - `outputs`: expected outputs, in order
+//! - `memory`: expected prefix of memory after the program halts
+//! - `relative-base`: expected relative base after the program halts
+//!
+//! Any engine implementing `Engine` can be checked against the same cases.
+
+use super::fuzz::{self, Outcome};
+use super::IntcodeBuilder;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub trait Engine {
+    fn name(&self) -> &str;
+    fn execute(&self, memory: &[i64], inputs: &[i64]) -> Outcome;
+}
+
+pub struct Interpreter;
+
+impl Engine for Interpreter {
+
+    fn name(&self) -> &str {
+        "intcode"
+    }
+
+    fn execute(&self, memory: &[i64], inputs: &[i64]) -> Outcome {
+        let mut vm = IntcodeBuilder::new()
+            .with_memory(memory)
+            .with_inputs(inputs)
+            .build();
+        vm.run();
+        Outcome {
+            memory: vm.memory,
+            outputs: vm.outputs,
+            relative_base: vm.relative_base
+        }
+    }
+
+}
+
+pub struct Reference;
+
+impl Engine for Reference {
+
+    fn name(&self) -> &str {
+        "reference"
+    }
+
+    fn execute(&self, memory: &[i64], inputs: &[i64]) -> Outcome {
+        fuzz::reference(memory, inputs).expect("reference interpreter faulted")
+    }
+
+}
+
+#[derive(Debug, Default)]
+pub struct Case {
+    pub name: String,
+    pub program: Vec<i64>,
+    pub memory_size: Option<usize>,
+    pub inputs: Vec<i64>,
+    pub outputs: Option<Vec<i64>>,
+    pub memory: Option<Vec<i64>>,
+    pub relative_base: Option<i64>
+}
+
+impl Case {
+
+    pub fn parse(name: &str, text: &str) -> Result<Case, String> {
+        let mut case = Case {
+            name: name.to_string(),
+            ..Default::default()
+        };
+        let mut has_program = false;
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let error = |message: &str| format!("{}:{}: {}", name, index + 1, message);
+            let mut parts = line.splitn(2, ':');
+            let key = parts.next().unwrap().trim();
+            let value = parts.next().ok_or_else(|| error("expected `key: value`"))?.trim();
+            let list = || values(value).map_err(|x| error(&x));
+            match key {
+                "program" => {
+                    case.program = list()?;
+                    has_program = true;
+                },
+                "memory-size" => case.memory_size = Some(value.parse().map_err(|_| error("invalid memory size"))?),
+                "inputs" => case.inputs = list()?,
+                "outputs" => case.outputs = Some(list()?),
+                "memory" => case.memory = Some(list()?),
+                "relative-base" => case.relative_base = Some(value.parse().map_err(|_| error("invalid relative base"))?),
+                _ => return Err(error(&format!("unknown key `{}`", key)))
+            }
+        }
+        if !has_program {
+            return Err(format!("{}: missing program", name));
+        }
+        Ok(case)
+    }
+
+    pub fn load(path: &Path) -> Result<Case, String> {
+        let name = path.file_stem().unwrap().to_string_lossy();
+        let text = fs::read_to_string(path).map_err(|x| format!("{}: {}", name, x))?;
+        Case::parse(&name, &text)
+    }
+
+    /// Runs the case, returning a description of every expectation that failed.
+    pub fn check(&self, engine: &dyn Engine) -> Vec<String> {
+        let mut memory = self.program.clone();
+        if let Some(size) = self.memory_size {
+            memory.resize(std::cmp::max(size, memory.len()), 0);
+        }
+        let outcome = engine.execute(&memory, &self.inputs);
+        let mut failures = Vec::new();
+        if let Some(outputs) = &self.outputs {
+            if outputs != &outcome.outputs {
+                failures.push(format!("outputs: expected {:?}, got {:?}", outputs, outcome.outputs));
+            }
+        }
+        if let Some(expected) = &self.memory {
+            let actual = &outcome.memory[0..std::cmp::min(expected.len(), outcome.memory.len())];
+            if &expected[0..] != actual {
+                failures.push(format!("memory: expected {:?}, got {:?}", expected, actual));
+            }
+        }
+        if let Some(base) = self.relative_base {
+            if base != outcome.relative_base {
+                failures.push(format!("relative base: expected {}, got {}", base, outcome.relative_base));
+            }
+        }
+        failures
+    }
+
+}
+
+fn values(input: &str) -> Result<Vec<i64>, String> {
+    input
+        .split(',')
+        .map(|x| x.trim())
+        .filter(|x| !x.is_empty())
+        .map(|x| x.parse::<i64>().map_err(|_| format!("invalid value `{}`", x)))
+        .collect()
+}
+
+pub fn cases_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("src/intcode/cases")
+}
+
+pub fn load_cases(dir: &Path) -> Result<Vec<Case>, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|x| format!("{}: {}", dir.display(), x))?
+        .filter_map(|entry| entry.ok().map(|x| x.path()))
+        .filter(|path| path.extension().is_some_and(|x| x == "txt"))
+        .collect();
+    paths.sort();
+    paths.iter().map(|path| Case::load(path)).collect()
+}
+
+/// Runs every case against `engine` and returns one line per failure.
+pub fn run_suite(engine: &dyn Engine, cases: &[Case]) -> Vec<String> {
+    cases.iter()
+        .flat_map(|case| {
+            case.check(engine)
+                .into_iter()
+                .map(move |failure| format!("[{}] {}: {}", engine.name(), case.name, failure))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_conforms(engine: &dyn Engine) {
+        let cases = load_cases(&cases_dir()).unwrap();
+        assert!(!cases.is_empty());
+        let failures = run_suite(engine, &cases);
+        assert!(failures.is_empty(), "\n{}", failures.join("\n"));
+    }
+
+    #[test]
+    fn interpreter_conforms() {
+        assert_conforms(&Interpreter);
+    }
+
+    #[test]
+    fn reference_conforms() {
+        assert_conforms(&Reference);
+    }
+
+    #[test]
+    fn reports_failed_expectations() {
+        let case = Case::parse("bad", "program: 104,1,99\noutputs: 2\nrelative-base: 5").unwrap();
+        let failures = case.check(&Interpreter);
+        assert_eq!(2, failures.len());
+    }
+
+    #[test]
+    fn rejects_malformed_cases() {
+        assert!(Case::parse("empty", "# nothing here").is_err());
+        assert!(Case::parse("bad", "program: 1,x,3").unwrap_err().contains("bad:1"));
+        assert!(Case::parse("bad", "program: 99\noutput: 1").unwrap_err().contains("unknown key"));
+    }
+
+}
@@ -0,0 +1,181 @@
+//! Records which addresses, opcodes and parameter modes a program exercises.
+
+use super::{Instruction, Parameter};
+use super::{OP_ADD, OP_CEQ, OP_CLT, OP_HALT, OP_INPUT, OP_JF, OP_JT, OP_MUL, OP_OUTPUT, OP_RBO};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+const OPCODES: [u8; 10] = [OP_ADD, OP_MUL, OP_INPUT, OP_OUTPUT, OP_JT, OP_JF, OP_CLT, OP_CEQ, OP_RBO, OP_HALT];
+
+/// Name, number of parameters read and number written for an opcode.
+fn signature(opcode: u8) -> (&'static str, usize, usize) {
+    match opcode {
+        OP_ADD => ("add", 2, 1),
+        OP_MUL => ("mul", 2, 1),
+        OP_INPUT => ("input", 0, 1),
+        OP_OUTPUT => ("output", 1, 0),
+        OP_JT => ("jt", 2, 0),
+        OP_JF => ("jf", 2, 0),
+        OP_CLT => ("clt", 2, 1),
+        OP_CEQ => ("ceq", 2, 1),
+        OP_RBO => ("rbo", 1, 0),
+        _ => ("halt", 0, 0)
+    }
+}
+
+/// Every valid mode combination for an opcode; written parameters can't be
+/// immediate.
+fn combinations(opcode: u8) -> Vec<Vec<Parameter>> {
+    let (_, reads, writes) = signature(opcode);
+    let mut combinations = vec![Vec::new()];
+    for index in 0..reads + writes {
+        let modes: &[Parameter] = if index < reads {
+            &[Parameter::Position, Parameter::Immediate, Parameter::Relative]
+        }
+        else {
+            &[Parameter::Position, Parameter::Relative]
+        };
+        combinations = combinations.into_iter()
+            .flat_map(|prefix| modes.iter().map(move |mode| {
+                let mut combination = prefix.clone();
+                combination.push(*mode);
+                combination
+            }))
+            .collect();
+    }
+    combinations
+}
+
+fn describe(modes: &[Parameter]) -> String {
+    if modes.is_empty() {
+        return "no parameters".to_string();
+    }
+    modes.iter()
+        .map(|mode| match mode {
+            Parameter::Position => "position",
+            Parameter::Immediate => "immediate",
+            Parameter::Relative => "relative"
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Coverage of one or more runs; enable with `IntcodeBuilder::with_coverage`.
+#[derive(Debug, Default, Clone)]
+pub struct Coverage {
+    pub addresses: BTreeSet<usize>,
+    pub opcodes: BTreeMap<u8, usize>,
+    pub modes: BTreeSet<(u8, Vec<Parameter>)>
+}
+
+impl Coverage {
+
+    pub fn new() -> Coverage {
+        Default::default()
+    }
+
+    pub(super) fn record(&mut self, address: usize, instruction: &Instruction) {
+        let (_, reads, writes) = signature(instruction.opcode);
+        let modes = [instruction.op1, instruction.op2, instruction.op3];
+        self.addresses.insert(address);
+        *self.opcodes.entry(instruction.opcode).or_insert(0) += 1;
+        self.modes.insert((instruction.opcode, modes[0..reads + writes].to_vec()));
+    }
+
+    /// Combines coverage from several runs, e.g. a whole test suite.
+    pub fn merge(&mut self, other: &Coverage) {
+        self.addresses.extend(other.addresses.iter());
+        for (opcode, count) in &other.opcodes {
+            *self.opcodes.entry(*opcode).or_insert(0) += count;
+        }
+        self.modes.extend(other.modes.iter().cloned());
+    }
+
+    /// Mode combinations never executed, by opcode.
+    pub fn untested(&self) -> Vec<(u8, Vec<Parameter>)> {
+        OPCODES.iter()
+            .flat_map(|opcode| combinations(*opcode).into_iter().map(move |modes| (*opcode, modes)))
+            .filter(|x| !self.modes.contains(x))
+            .collect()
+    }
+
+}
+
+impl fmt::Display for Coverage {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let total: usize = OPCODES.iter().map(|x| combinations(*x).len()).sum();
+        let untested = self.untested();
+        writeln!(f, "addresses: {} executed", self.addresses.len())?;
+        writeln!(f, "opcodes: {}/{} executed", self.opcodes.len(), OPCODES.len())?;
+        writeln!(f, "modes: {}/{} combinations", total - untested.len(), total)?;
+        for opcode in OPCODES.iter() {
+            let (name, _, _) = signature(*opcode);
+            let count = self.opcodes.get(opcode).copied().unwrap_or(0);
+            let missing: Vec<&Vec<Parameter>> = untested.iter()
+                .filter(|(x, _)| x == opcode)
+                .map(|(_, modes)| modes)
+                .collect();
+            writeln!(f, "  {}: {} executions, {} untested", name, count, missing.len())?;
+            for modes in missing {
+                writeln!(f, "    {}", describe(modes))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::intcode::conformance;
+    use crate::intcode::IntcodeBuilder;
+
+    #[test]
+    fn records_executed_instructions() {
+        let mut vm = IntcodeBuilder::new()
+            .with_program("1101,1,2,9,109,9,204,0,99,0")
+            .with_coverage()
+            .build();
+        vm.run();
+        let coverage = vm.coverage.unwrap();
+        assert_eq!(vec![0, 4, 6, 8], coverage.addresses.iter().copied().collect::<Vec<_>>());
+        assert_eq!(Some(&1), coverage.opcodes.get(&OP_ADD));
+        assert!(coverage.modes.contains(&(OP_OUTPUT, vec![Parameter::Relative])));
+        assert!(coverage.modes.contains(&(OP_ADD, vec![Parameter::Immediate, Parameter::Immediate, Parameter::Position])));
+    }
+
+    #[test]
+    fn reports_untested_combinations() {
+        let mut vm = IntcodeBuilder::new()
+            .with_program("99")
+            .with_coverage()
+            .build();
+        vm.run();
+        let coverage = vm.coverage.unwrap();
+        let untested = coverage.untested();
+        assert_eq!(98, untested.len());
+        assert!(untested.contains(&(OP_CLT, vec![Parameter::Position, Parameter::Position, Parameter::Relative])));
+        let summary = coverage.to_string();
+        assert!(summary.contains("modes: 1/99 combinations"));
+        assert!(summary.contains("clt: 0 executions, 18 untested"));
+    }
+
+    #[test]
+    fn conformance_cases_execute_every_opcode() {
+        let mut total = Coverage::new();
+        for case in conformance::load_cases(&conformance::cases_dir()).unwrap() {
+            let mut memory = case.program.clone();
+            memory.resize(std::cmp::max(case.memory_size.unwrap_or(0), memory.len()), 0);
+            let mut vm = IntcodeBuilder::new()
+                .with_memory(&memory)
+                .with_inputs(&case.inputs)
+                .with_coverage()
+                .build();
+            vm.run();
+            total.merge(vm.coverage.as_ref().unwrap());
+        }
+        assert_eq!(OPCODES.len(), total.opcodes.len(), "\n{}", total);
+    }
+
+}
@@ -0,0 +1,500 @@
+//! Differential fuzzing of `Intcode` against a small reference interpreter.
+//!
+//! Programs are generated from a tiny instruction list (`Case`) rather than raw
+//! memory so that every generated program is valid: writes only land in the
+//! data region, the relative base stays within bounds and jumps only go
+//! forward, which guarantees termination. Failing cases are shrunk by removing
+//! instructions and simplifying operands until no smaller failing case exists.
+
+use super::IntcodeBuilder;
+use std::convert::TryFrom;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+const MAX_OPS: i64 = 24;
+const SLOTS: i64 = 8;
+const MAX_RBO: usize = 4;
+const RBO_STEP: i64 = 4;
+const BASE_RANGE: i64 = MAX_RBO as i64 * RBO_STEP;
+const DATA_LEN: i64 = 2 * BASE_RANGE + SLOTS;
+
+/// Deterministic splitmix64 generator, so a seed is enough to replay a run.
+pub struct Rng(u64);
+
+impl Rng {
+
+    pub fn new(seed: u64) -> Rng {
+        Rng(seed)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `low..high`.
+    pub fn range(&mut self, low: i64, high: i64) -> i64 {
+        low + (self.next() % (high - low) as u64) as i64
+    }
+
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Operand {
+    Immediate(i64),
+    /// Index into the data region.
+    Position(i64),
+    /// Offset from the relative base, which always points into the data region.
+    Relative(i64)
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Op {
+    Add(Operand, Operand, Operand),
+    Mul(Operand, Operand, Operand),
+    Input(Operand),
+    Output(Operand),
+    /// Jump targets are indices into the op list; `ops.len()` is the final halt.
+    JumpIfTrue(Operand, usize),
+    JumpIfFalse(Operand, usize),
+    LessThan(Operand, Operand, Operand),
+    Equals(Operand, Operand, Operand),
+    AdjustBase(i64)
+}
+
+impl Op {
+
+    fn len(&self) -> usize {
+        match self {
+            Op::Input(_) | Op::Output(_) | Op::AdjustBase(_) => 2,
+            Op::JumpIfTrue(..) | Op::JumpIfFalse(..) => 3,
+            _ => 4
+        }
+    }
+
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Case {
+    pub ops: Vec<Op>,
+    pub data: Vec<i64>,
+    pub inputs: Vec<i64>
+}
+
+impl Case {
+
+    pub fn generate(rng: &mut Rng) -> Case {
+        let count = rng.range(1, MAX_OPS + 1) as usize;
+        let mut ops = Vec::with_capacity(count);
+        let mut inputs = Vec::new();
+        let mut adjustments = 0;
+        for index in 0..count {
+            let target = rng.range(index as i64 + 1, count as i64 + 1) as usize;
+            let op = match rng.range(0, 9) {
+                0 => Op::Add(read(rng), read(rng), write(rng)),
+                1 => Op::Mul(read(rng), read(rng), write(rng)),
+                2 => {
+                    inputs.push(rng.range(-100, 100));
+                    Op::Input(write(rng))
+                },
+                3 => Op::Output(read(rng)),
+                4 => Op::JumpIfTrue(read(rng), target),
+                5 => Op::JumpIfFalse(read(rng), target),
+                6 => Op::LessThan(read(rng), read(rng), write(rng)),
+                7 => Op::Equals(read(rng), read(rng), write(rng)),
+                _ if adjustments < MAX_RBO => {
+                    adjustments += 1;
+                    Op::AdjustBase(rng.range(-RBO_STEP, RBO_STEP + 1))
+                },
+                _ => Op::Output(read(rng))
+            };
+            ops.push(op);
+        }
+        let data = (0..DATA_LEN).map(|_| rng.range(-50, 50)).collect();
+        Case {
+            ops,
+            data,
+            inputs
+        }
+    }
+
+    /// Lays out the program as a prologue that points the relative base at the
+    /// middle of the data region, the ops, a halt, and then the data region.
+    pub fn assemble(&self) -> Vec<i64> {
+        let mut addresses = Vec::with_capacity(self.ops.len() + 1);
+        let mut pc = 2;
+        for op in &self.ops {
+            addresses.push(pc as i64);
+            pc += op.len();
+        }
+        addresses.push(pc as i64);
+        let data_start = pc as i64 + 1;
+
+        let mut image = vec![109, data_start + BASE_RANGE];
+        let encode = |image: &mut Vec<i64>, opcode: i64, operands: &[Operand]| {
+            let mut instruction = opcode;
+            let mut scale = 100;
+            let mut values = Vec::with_capacity(operands.len());
+            for operand in operands {
+                let (mode, value) = match *operand {
+                    Operand::Position(index) => (0, data_start + index),
+                    Operand::Immediate(value) => (1, value),
+                    Operand::Relative(offset) => (2, offset)
+                };
+                instruction += mode * scale;
+                scale *= 10;
+                values.push(value);
+            }
+            image.push(instruction);
+            image.extend(values);
+        };
+        for op in &self.ops {
+            match *op {
+                Op::Add(a, b, c) => encode(&mut image, 1, &[a, b, c]),
+                Op::Mul(a, b, c) => encode(&mut image, 2, &[a, b, c]),
+                Op::Input(a) => encode(&mut image, 3, &[a]),
+                Op::Output(a) => encode(&mut image, 4, &[a]),
+                Op::JumpIfTrue(a, t) => encode(&mut image, 5, &[a, Operand::Immediate(addresses[t])]),
+                Op::JumpIfFalse(a, t) => encode(&mut image, 6, &[a, Operand::Immediate(addresses[t])]),
+                Op::LessThan(a, b, c) => encode(&mut image, 7, &[a, b, c]),
+                Op::Equals(a, b, c) => encode(&mut image, 8, &[a, b, c]),
+                Op::AdjustBase(offset) => encode(&mut image, 9, &[Operand::Immediate(offset)])
+            }
+        }
+        image.push(99);
+        image.extend(&self.data);
+        image
+    }
+
+}
+
+fn read(rng: &mut Rng) -> Operand {
+    match rng.range(0, 3) {
+        0 => Operand::Immediate(rng.range(-50, 50)),
+        1 => Operand::Position(rng.range(0, DATA_LEN)),
+        _ => Operand::Relative(rng.range(0, SLOTS))
+    }
+}
+
+fn write(rng: &mut Rng) -> Operand {
+    match rng.range(0, 2) {
+        0 => Operand::Position(rng.range(0, DATA_LEN)),
+        _ => Operand::Relative(rng.range(0, SLOTS))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    pub memory: Vec<i64>,
+    pub outputs: Vec<i64>,
+    pub relative_base: i64
+}
+
+/// Straightforward interpreter used as the source of truth. Returns `None` if
+/// the program faults (bad opcode, bad address or arithmetic overflow), in
+/// which case it is not a valid test program.
+pub fn reference(memory: &[i64], inputs: &[i64]) -> Option<Outcome> {
+    let mut memory = memory.to_vec();
+    let mut inputs = inputs.iter();
+    let mut outputs = Vec::new();
+    let mut base = 0;
+    let mut pc = 0;
+    loop {
+        let instruction = *memory.get(pc)?;
+        let address = |memory: &[i64], n: usize| -> Option<usize> {
+            let mode = (instruction / 10i64.pow(n as u32 + 1)) % 10;
+            let raw = *memory.get(pc + n)?;
+            let address = match mode {
+                0 => raw,
+                1 => (pc + n) as i64,
+                2 => base + raw,
+                _ => return None
+            };
+            if address < 0 || address as usize >= memory.len() {
+                return None;
+            }
+            Some(address as usize)
+        };
+        let param = |memory: &[i64], n: usize| address(memory, n).map(|a| memory[a]);
+        let target = |memory: &[i64], n: usize| {
+            if (instruction / 10i64.pow(n as u32 + 1)) % 10 == 1 {
+                return None;
+            }
+            address(memory, n)
+        };
+        match instruction % 100 {
+            1 => {
+                let value = param(&memory, 1)?.checked_add(param(&memory, 2)?)?;
+                let dest = target(&memory, 3)?;
+                memory[dest] = value;
+                pc += 4;
+            },
+            2 => {
+                let value = param(&memory, 1)?.checked_mul(param(&memory, 2)?)?;
+                let dest = target(&memory, 3)?;
+                memory[dest] = value;
+                pc += 4;
+            },
+            3 => {
+                let value = inputs.next().copied().unwrap_or(0);
+                let dest = target(&memory, 1)?;
+                memory[dest] = value;
+                pc += 2;
+            },
+            4 => {
+                outputs.push(param(&memory, 1)?);
+                pc += 2;
+            },
+            5 | 6 => {
+                let jump = (param(&memory, 1)? != 0) == (instruction % 100 == 5);
+                let dest = param(&memory, 2)?;
+                pc = if jump { usize::try_from(dest).ok()? } else { pc + 3 };
+            },
+            7 => {
+                let value = (param(&memory, 1)? < param(&memory, 2)?) as i64;
+                let dest = target(&memory, 3)?;
+                memory[dest] = value;
+                pc += 4;
+            },
+            8 => {
+                let value = (param(&memory, 1)? == param(&memory, 2)?) as i64;
+                let dest = target(&memory, 3)?;
+                memory[dest] = value;
+                pc += 4;
+            },
+            9 => {
+                base = base.checked_add(param(&memory, 1)?)?;
+                pc += 2;
+            },
+            99 => break,
+            _ => return None
+        }
+    }
+    Some(Outcome {
+        memory,
+        outputs,
+        relative_base: base
+    })
+}
+
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub case: Case,
+    pub expected: Outcome,
+    /// The VM's outcome, or the panic message if it crashed.
+    pub actual: Result<Outcome, String>
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let program: Vec<String> = self.case.assemble().iter().map(|x| x.to_string()).collect();
+        writeln!(f, "program: {}", program.join(","))?;
+        writeln!(f, "inputs: {:?}", self.case.inputs)?;
+        writeln!(f, "expected: {:?}", self.expected)?;
+        write!(f, "actual: {:?}", self.actual)
+    }
+}
+
+/// Runs a case through both interpreters. Cases the reference rejects are
+/// not valid programs and are treated as passing.
+pub fn check(case: &Case) -> Result<(), Box<Mismatch>> {
+    let image = case.assemble();
+    let expected = match reference(&image, &case.inputs) {
+        Some(outcome) => outcome,
+        None => return Ok(())
+    };
+    let actual = panic::catch_unwind(AssertUnwindSafe(|| {
+        let mut vm = IntcodeBuilder::new()
+            .with_memory(&image)
+            .with_inputs(&case.inputs)
+            .build();
+        vm.run();
+        Outcome {
+            memory: vm.memory,
+            outputs: vm.outputs,
+            relative_base: vm.relative_base
+        }
+    }));
+    let actual = actual.map_err(|payload| {
+        payload.downcast_ref::<&str>().map(|x| x.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "panic".to_string())
+    });
+    if actual.as_ref() == Ok(&expected) {
+        return Ok(());
+    }
+    Err(Box::new(Mismatch {
+        case: case.clone(),
+        expected,
+        actual
+    }))
+}
+
+/// Checks `iterations` generated programs, returning the first mismatch after
+/// shrinking it to a minimal reproducer.
+pub fn fuzz(seed: u64, iterations: usize) -> Result<(), Box<Mismatch>> {
+    let mut rng = Rng::new(seed);
+    for _ in 0..iterations {
+        let case = Case::generate(&mut rng);
+        if check(&case).is_err() {
+            let case = shrink(case, |x| check(x).is_err());
+            return check(&case);
+        }
+    }
+    Ok(())
+}
+
+/// Greedily applies the first simplification that keeps `failing` true until
+/// none apply.
+pub fn shrink<F>(mut case: Case, failing: F) -> Case
+    where F: Fn(&Case) -> bool {
+    'outer: loop {
+        for candidate in simplifications(&case) {
+            if failing(&candidate) {
+                case = candidate;
+                continue 'outer;
+            }
+        }
+        return case;
+    }
+}
+
+fn simplifications(case: &Case) -> Vec<Case> {
+    let mut candidates = Vec::new();
+
+    for index in 0..case.ops.len() {
+        let mut candidate = case.clone();
+        candidate.ops.remove(index);
+        for op in candidate.ops.iter_mut() {
+            match op {
+                Op::JumpIfTrue(_, target) | Op::JumpIfFalse(_, target) if *target > index => *target -= 1,
+                _ => ()
+            }
+        }
+        candidates.push(candidate);
+    }
+
+    if !case.inputs.is_empty() {
+        let mut candidate = case.clone();
+        candidate.inputs.pop();
+        candidates.push(candidate);
+    }
+
+    for index in 0..case.ops.len() {
+        for op in simpler_ops(case.ops[index]) {
+            let mut candidate = case.clone();
+            candidate.ops[index] = op;
+            candidates.push(candidate);
+        }
+    }
+
+    let values = case.data.iter().enumerate()
+        .map(|(index, value)| (false, index, *value))
+        .chain(case.inputs.iter().enumerate().map(|(index, value)| (true, index, *value)));
+    for (is_input, index, value) in values {
+        for simpler in simpler_values(value) {
+            let mut candidate = case.clone();
+            if is_input {
+                candidate.inputs[index] = simpler;
+            }
+            else {
+                candidate.data[index] = simpler;
+            }
+            candidates.push(candidate);
+        }
+    }
+
+    candidates
+}
+
+fn simpler_values(value: i64) -> Vec<i64> {
+    match value {
+        0 => vec![],
+        _ if value.abs() == 1 => vec![0],
+        _ => vec![0, value / 2]
+    }
+}
+
+fn simpler_operands(operand: Operand, writable: bool) -> Vec<Operand> {
+    let mut operands: Vec<Operand> = match operand {
+        Operand::Immediate(value) => simpler_values(value).into_iter().map(Operand::Immediate).collect(),
+        Operand::Position(index) => simpler_values(index).into_iter().map(Operand::Position).collect(),
+        Operand::Relative(offset) => simpler_values(offset).into_iter().map(Operand::Relative).collect()
+    };
+    match operand {
+        Operand::Position(_) if !writable => operands.push(Operand::Immediate(0)),
+        Operand::Relative(_) => operands.push(Operand::Position(0)),
+        _ => ()
+    }
+    operands
+}
+
+fn simpler_ops(op: Op) -> Vec<Op> {
+    let binary = |a, b, c, make: fn(Operand, Operand, Operand) -> Op| {
+        let mut ops: Vec<Op> = simpler_operands(a, false).into_iter().map(|x| make(x, b, c)).collect();
+        ops.extend(simpler_operands(b, false).into_iter().map(|x| make(a, x, c)));
+        ops.extend(simpler_operands(c, true).into_iter().map(|x| make(a, b, x)));
+        ops
+    };
+    match op {
+        Op::Add(a, b, c) => binary(a, b, c, Op::Add),
+        Op::Mul(a, b, c) => binary(a, b, c, Op::Mul),
+        Op::LessThan(a, b, c) => binary(a, b, c, Op::LessThan),
+        Op::Equals(a, b, c) => binary(a, b, c, Op::Equals),
+        Op::Input(a) => simpler_operands(a, true).into_iter().map(Op::Input).collect(),
+        Op::Output(a) => simpler_operands(a, false).into_iter().map(Op::Output).collect(),
+        Op::JumpIfTrue(a, t) => simpler_operands(a, false).into_iter().map(|x| Op::JumpIfTrue(x, t)).collect(),
+        Op::JumpIfFalse(a, t) => simpler_operands(a, false).into_iter().map(|x| Op::JumpIfFalse(x, t)).collect(),
+        Op::AdjustBase(offset) => simpler_values(offset).into_iter().map(Op::AdjustBase).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn vm_matches_reference() {
+        for seed in 0..8 {
+            if let Err(mismatch) = fuzz(seed, 500) {
+                panic!("seed {} produced a mismatch\n{}", seed, mismatch);
+            }
+        }
+    }
+
+    #[test]
+    fn reference_runs_quine() {
+        let program = [109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99];
+        let mut memory = program.to_vec();
+        memory.resize(128, 0);
+        let outcome = reference(&memory, &[]).unwrap();
+        assert_eq!(&program[0..], &outcome.outputs[0..]);
+    }
+
+    #[test]
+    fn reference_rejects_overflow() {
+        let program = [1102,4611686018427387904,4,0,99];
+        assert_eq!(None, reference(&program, &[]));
+    }
+
+    #[test]
+    fn shrinks_to_minimal_reproducer() {
+        let outputs_large_value = |case: &Case| {
+            reference(&case.assemble(), &case.inputs)
+                .map(|outcome| outcome.outputs.iter().any(|x| *x > 10))
+                .unwrap_or(false)
+        };
+        let mut rng = Rng::new(7);
+        let case = (0..).map(|_| Case::generate(&mut rng))
+            .find(|case| case.ops.len() > 4 && outputs_large_value(case))
+            .unwrap();
+        let case = shrink(case, outputs_large_value);
+        assert_eq!(1, case.ops.len());
+        assert!(outputs_large_value(&case));
+    }
+
+}
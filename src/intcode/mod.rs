@@ -1,14 +1,52 @@
+//! The Intcode virtual machine from Advent of Code 2019.
+//!
+//! Programs are lists of integers; build a machine with [`IntcodeBuilder`] and
+//! drive it with [`Intcode::run`], [`Intcode::run_yield`] to stop after each
+//! output, or [`Intcode::run_until_input`] to stop when it needs more input:
+//!
+//! ```
+//! use aoc::intcode::IntcodeBuilder;
+//!
+//! // Reads a value, adds 5 and outputs the sum.
+//! let mut vm = IntcodeBuilder::new()
+//!     .with_program("3,9,1001,9,5,9,4,9,99,0")
+//!     .with_inputs(&[37])
+//!     .build();
+//! vm.run();
+//! assert_eq!(vec![42], vm.outputs);
+//! ```
+
+pub mod compiler;
+#[cfg(test)]
+mod conformance;
+mod coverage;
+#[cfg(test)]
+mod fuzz;
+pub mod optimizer;
+
 use std::collections::VecDeque;
 
+pub use self::coverage::Coverage;
+
+/// Machine state. Every field is public so callers can inspect memory and
+/// outputs, or feed inputs between runs.
 #[derive(Debug)]
 pub struct Intcode {
+    /// Set once the program executes opcode 99.
     pub halted: bool,
     pub memory: Vec<i64>,
+    /// Address of the next instruction.
     pub pc: usize,
+    /// Values consumed, front first, by input instructions.
     pub inputs: VecDeque<i64>,
+    /// Values written by output instructions, in order.
     pub outputs: Vec<i64>,
+    /// Set by an output instruction; [`Intcode::run_yield`] stops on it.
     pub yielding: bool,
-    pub relative_base: i64
+    /// Base address for relative-mode parameters.
+    pub relative_base: i64,
+    /// Execution coverage, if enabled with [`IntcodeBuilder::with_coverage`].
+    pub coverage: Option<Coverage>
 }
 
 const OP_HALT: u8 = 99;
@@ -22,8 +60,9 @@ const OP_CLT: u8 = 7;
 const OP_CEQ: u8 = 8;
 const OP_RBO: u8 = 9;
 
-#[derive(Debug, Copy, Clone)]
-enum Parameter {
+/// Parameter mode: how an instruction operand is interpreted.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Parameter {
     Position,
     Immediate,
     Relative
@@ -48,10 +87,12 @@ impl From<i64> for Parameter {
     }
 }
 
+/// Configures memory, inputs and options for a new [`Intcode`] machine.
 pub struct IntcodeBuilder {
     memory: Vec<i64>,
     inputs: Vec<i64>,
-    relative_base: i64
+    relative_base: i64,
+    coverage: bool
 }
 
 impl IntcodeBuilder {
@@ -60,10 +101,12 @@ impl IntcodeBuilder {
         IntcodeBuilder {
             memory: Vec::new(),
             inputs: Vec::new(),
-            relative_base: 0
+            relative_base: 0,
+            coverage: false
         }
     }
 
+    /// Copies `memory` to the start of memory, growing it if needed.
     pub fn with_memory(mut self, memory: &[i64]) -> IntcodeBuilder {
         let size = std::cmp::max(memory.len(), self.memory.len());
         self.memory.resize(size, 0);
@@ -72,6 +115,7 @@ impl IntcodeBuilder {
         self
     }
 
+    /// Queues input values.
     pub fn with_inputs(mut self, inputs: &[i64]) -> IntcodeBuilder {
         self.inputs.extend(inputs.iter());
         self
@@ -82,11 +126,20 @@ impl IntcodeBuilder {
         self
     }
 
+    /// Loads a comma-separated program; see [`Intcode::compile`].
     pub fn with_program(self, input: &str) -> IntcodeBuilder {
         let program = Intcode::compile(input);
         self.with_memory(&program)
     }
 
+    /// Records which instructions and parameter modes execute.
+    pub fn with_coverage(mut self) -> IntcodeBuilder {
+        self.coverage = true;
+        self
+    }
+
+    /// Resizes memory to `size` cells, zero-filled; programs that address
+    /// beyond their image need this.
     pub fn with_memory_size(mut self, size: usize) -> IntcodeBuilder {
         self.memory.resize(size, Default::default());
         self
@@ -97,11 +150,20 @@ impl IntcodeBuilder {
         vm.reset(self.memory);
         vm.relative_base = self.relative_base;
         vm.inputs.extend(self.inputs.iter());
+        if self.coverage {
+            vm.coverage = Some(Coverage::new());
+        }
         vm
     }
 
 }
 
+impl Default for IntcodeBuilder {
+    fn default() -> IntcodeBuilder {
+        IntcodeBuilder::new()
+    }
+}
+
 impl Intcode {
 
     pub fn new() -> Intcode {
@@ -112,14 +174,16 @@ impl Intcode {
             pc: 0,
             halted: false,
             yielding: false,
-            relative_base: 0
+            relative_base: 0,
+            coverage: None
         }
     }
 
+    /// Parses a comma-separated program. Panics on anything but integers.
     pub fn compile(input: &str) -> Vec<i64> {
         input
             .split(",")
-            .map(|x| x.parse::<i64>().unwrap())
+            .map(|x| x.trim().parse::<i64>().unwrap())
             .collect()
     }
 
@@ -137,6 +201,7 @@ impl Intcode {
         }
     }
 
+    /// Loads `memory` and clears all other state, except coverage.
     pub fn reset(&mut self, memory: Vec<i64>) {
         self.memory = memory;
         self.pc = 0;
@@ -147,11 +212,16 @@ impl Intcode {
         self.relative_base = 0;
     }
 
+    /// Executes a single instruction.
     pub fn cycle(&mut self) {
         if self.halted {
             return
         }
+        let pc = self.pc;
         let op = self.decode();
+        if let Some(coverage) = self.coverage.as_mut() {
+            coverage.record(pc, &op);
+        }
         match op.opcode {
             OP_ADD => self.op_add(&op),
             OP_MUL => self.op_mul(&op),
@@ -167,6 +237,7 @@ impl Intcode {
         }
     }
 
+    /// Runs until the program outputs a value or halts.
     pub fn run_yield(&mut self) {
         while !self.halted {
             self.yielding = false;
@@ -177,12 +248,27 @@ impl Intcode {
         }
     }
 
+    /// Runs until the program halts. Input instructions read 0 once the input
+    /// queue is empty.
     pub fn run(&mut self) {
         while !self.halted {
             self.cycle();
         }
     }
 
+    /// Runs until the program halts or [`Intcode::awaiting_input`]; queue more
+    /// inputs and call again to resume.
+    pub fn run_until_input(&mut self) {
+        while !self.halted && !self.awaiting_input() {
+            self.cycle();
+        }
+    }
+
+    /// True if the next instruction reads input and none is queued.
+    pub fn awaiting_input(&self) -> bool {
+        !self.halted && self.inputs.is_empty() && self.memory.get(self.pc).is_some_and(|x| x % 100 == OP_INPUT as i64)
+    }
+
     pub fn read(&self, addr: i64) -> i64 {
         if addr < 0 {
             panic!("attempt to read from negative memory address");
@@ -283,4 +369,34 @@ impl Intcode {
         self.write(dest, a + b);
     }
 
-}
\ No newline at end of file
+}
+
+impl Default for Intcode {
+    fn default() -> Intcode {
+        Intcode::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn pauses_when_awaiting_input() {
+        // Outputs the sum of two inputs.
+        let mut vm = IntcodeBuilder::new()
+            .with_program("3,11,3,12,1,11,12,13,4,13,99,0,0,0")
+            .with_inputs(&[20])
+            .build();
+        vm.run_until_input();
+        assert!(vm.awaiting_input());
+        assert_eq!(2, vm.pc);
+        vm.inputs.push_back(22);
+        vm.run_until_input();
+        assert!(vm.halted);
+        assert!(!vm.awaiting_input());
+        assert_eq!(vec![42], vm.outputs);
+    }
+
+}
@@ -0,0 +1,477 @@
+//! Constant folding and jump simplification on Intcode program images.
+//!
+//! A cell is treated as a constant when no reachable instruction writes to it,
+//! so operands are known if they are immediate or read such a cell. Using that:
+//!
+//! - jumps whose condition is known are removed (never taken) or rewritten as
+//!   unconditional jumps (always taken), and unconditional jumps to the next
+//!   instruction are removed;
+//! - instructions that became unreachable are removed unless their cells are
+//!   read as data;
+//! - arithmetic and comparisons in the straight-line entry sequence whose
+//!   inputs are known, and whose destination nothing else writes, are removed
+//!   and their result stored in the image instead.
+//!
+//! Removing instructions shifts the rest of the image, so every position-mode
+//! address and jump target is relocated. The analysis is conservative: images
+//! with self-modifying code, indirect jumps or relative addressing are
+//! returned unchanged, with the reason noted in the report.
+
+use super::IntcodeBuilder;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fmt;
+
+const OP_ADD: i64 = 1;
+const OP_MUL: i64 = 2;
+const OP_INPUT: i64 = 3;
+const OP_OUTPUT: i64 = 4;
+const OP_JT: i64 = 5;
+const OP_JF: i64 = 6;
+const OP_CLT: i64 = 7;
+const OP_CEQ: i64 = 8;
+const OP_RBO: i64 = 9;
+const OP_HALT: i64 = 99;
+
+#[derive(Debug, Clone)]
+struct Instruction {
+    address: usize,
+    opcode: i64,
+    modes: Vec<i64>,
+    params: Vec<i64>
+}
+
+impl Instruction {
+
+    fn decode(image: &[i64], address: usize) -> Result<Instruction, String> {
+        let value = image[address];
+        let opcode = value % 100;
+        let (count, writes) = match opcode {
+            OP_ADD | OP_MUL | OP_CLT | OP_CEQ => (3, true),
+            OP_INPUT => (1, true),
+            OP_OUTPUT | OP_RBO => (1, false),
+            OP_JT | OP_JF => (2, false),
+            OP_HALT => (0, false),
+            _ => return Err(format!("invalid opcode {} at @{}", value, address))
+        };
+        if address + count >= image.len() {
+            return Err(format!("truncated instruction at @{}", address));
+        }
+        let modes: Vec<i64> = (0..count).map(|n| (value / 10i64.pow(n as u32 + 2)) % 10).collect();
+        if modes.iter().any(|x| *x > 2) || (writes && modes[count - 1] == 1) {
+            return Err(format!("invalid parameter mode {} at @{}", value, address));
+        }
+        Ok(Instruction {
+            address,
+            opcode,
+            modes,
+            params: image[address + 1..=address + count].to_vec()
+        })
+    }
+
+    fn len(&self) -> usize {
+        self.params.len() + 1
+    }
+
+    fn next(&self) -> usize {
+        self.address + self.len()
+    }
+
+    fn is_jump(&self) -> bool {
+        self.opcode == OP_JT || self.opcode == OP_JF
+    }
+
+    /// Address written by this instruction, if it writes.
+    fn destination(&self) -> Option<i64> {
+        match self.opcode {
+            OP_ADD | OP_MUL | OP_CLT | OP_CEQ | OP_INPUT => self.params.last().copied(),
+            _ => None
+        }
+    }
+
+    /// Addresses of position-mode reads.
+    fn reads(&self) -> Vec<i64> {
+        let inputs = if self.destination().is_some() { self.params.len() - 1 } else { self.params.len() };
+        (0..inputs).filter(|n| self.modes[*n] == 0).map(|n| self.params[n]).collect()
+    }
+
+}
+
+struct Analysis {
+    instructions: BTreeMap<usize, Instruction>,
+    writers: HashMap<i64, usize>
+}
+
+impl Analysis {
+
+    fn known(&self, image: &[i64], instruction: &Instruction, n: usize) -> Option<i64> {
+        let param = instruction.params[n];
+        match instruction.modes[n] {
+            1 => Some(param),
+            0 if !self.writers.contains_key(&param) => image.get(param as usize).copied(),
+            _ => None
+        }
+    }
+
+    /// Whether a jump is taken, if its condition is known.
+    fn taken(&self, image: &[i64], jump: &Instruction) -> Option<bool> {
+        self.known(image, jump, 0).map(|x| (x != 0) == (jump.opcode == OP_JT))
+    }
+
+}
+
+/// Decodes every instruction reachable from address 0. With `analysis`, jumps
+/// with a known condition only follow the edge that is actually taken.
+fn explore(image: &[i64], analysis: Option<&Analysis>) -> Result<BTreeMap<usize, Instruction>, String> {
+    let mut instructions = BTreeMap::new();
+    let mut pending = vec![0];
+    while let Some(address) = pending.pop() {
+        if instructions.contains_key(&address) {
+            continue;
+        }
+        if address >= image.len() {
+            return Err(format!("execution runs past the end of the image at @{}", address));
+        }
+        let instruction = Instruction::decode(image, address)?;
+        if instruction.is_jump() {
+            if instruction.modes[1] != 1 {
+                return Err(format!("indirect jump at @{}", address));
+            }
+            // Immediate conditions are always known; anything that rewrites
+            // them is rejected as self-modifying code.
+            let taken = match analysis {
+                Some(analysis) => analysis.taken(image, &instruction),
+                None if instruction.modes[0] == 1 => Some((instruction.params[0] != 0) == (instruction.opcode == OP_JT)),
+                None => None
+            };
+            if taken != Some(false) {
+                pending.push(instruction.params[1] as usize);
+            }
+            if taken != Some(true) {
+                pending.push(instruction.next());
+            }
+        }
+        else if instruction.opcode != OP_HALT {
+            pending.push(instruction.next());
+        }
+        instructions.insert(address, instruction);
+    }
+
+    let mut end = 0;
+    for instruction in instructions.values() {
+        if instruction.address < end {
+            return Err(format!("overlapping instructions at @{}", instruction.address));
+        }
+        end = instruction.next();
+    }
+    Ok(instructions)
+}
+
+fn analyze(image: &[i64]) -> Result<Analysis, String> {
+    let instructions = explore(image, None)?;
+    let mut writers = HashMap::new();
+    for instruction in instructions.values() {
+        if instruction.opcode == OP_RBO || instruction.modes.contains(&2) {
+            return Err(format!("relative addressing at @{}", instruction.address));
+        }
+        if let Some(dest) = instruction.destination() {
+            *writers.entry(dest).or_insert(0) += 1;
+        }
+    }
+    for instruction in instructions.values() {
+        let cells = instruction.address as i64..instruction.next() as i64;
+        if let Some(dest) = cells.clone().find(|x| writers.contains_key(x)) {
+            return Err(format!("self-modifying code writes to @{}", dest));
+        }
+    }
+    Ok(Analysis {
+        instructions,
+        writers
+    })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// Address of the affected instruction in the original image.
+    pub address: usize,
+    pub description: String
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+    pub original_len: usize,
+    pub optimized_len: usize,
+    pub changes: Vec<Change>,
+    /// Why optimization stopped early, if it did.
+    pub skipped: Option<String>
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "image: {} -> {} cells", self.original_len, self.optimized_len)?;
+        for change in &self.changes {
+            writeln!(f, "@{}: {}", change.address, change.description)?;
+        }
+        if let Some(reason) = &self.skipped {
+            writeln!(f, "skipped: {}", reason)?;
+        }
+        Ok(())
+    }
+}
+
+/// Optimizes `image`, returning the new image and a report of every change.
+/// Repeats until an iteration finds nothing more to do.
+pub fn optimize(image: &[i64]) -> (Vec<i64>, Report) {
+    let mut report = Report {
+        original_len: image.len(),
+        ..Default::default()
+    };
+    let mut image = image.to_vec();
+    // Maps addresses in the current image back to the original image.
+    let mut origin: Vec<usize> = (0..image.len()).collect();
+    loop {
+        match pass(&image) {
+            Ok(None) => break,
+            Ok(Some((next, changes, relocation))) => {
+                report.changes.extend(changes.into_iter().map(|mut change| {
+                    change.address = origin[change.address];
+                    change
+                }));
+                let mut next_origin = vec![0; next.len()];
+                for (old, new) in relocation.iter().enumerate() {
+                    if let Some(new) = new {
+                        next_origin[*new] = origin[old];
+                    }
+                }
+                origin = next_origin;
+                image = next;
+            },
+            Err(reason) => {
+                report.skipped = Some(reason);
+                break;
+            }
+        }
+    }
+    report.optimized_len = image.len();
+    (image, report)
+}
+
+type Pass = Option<(Vec<i64>, Vec<Change>, Vec<Option<usize>>)>;
+
+fn pass(image: &[i64]) -> Result<Pass, String> {
+    let analysis = analyze(image)?;
+    let live = explore(image, Some(&analysis))?;
+
+    let mut removed: HashSet<usize> = HashSet::new();
+    let mut rewritten: Vec<(usize, Vec<i64>)> = Vec::new();
+    let mut folded: Vec<(usize, i64)> = Vec::new();
+    let mut changes = Vec::new();
+    let mut change = |address: usize, description: String| {
+        changes.push(Change {
+            address,
+            description
+        });
+    };
+
+    // Instructions whose cells are read as data have to stay where they are.
+    let reads: HashSet<i64> = live.values().flat_map(|x| x.reads()).collect();
+    let removable = |instruction: &Instruction| {
+        !(instruction.address as i64..instruction.next() as i64).any(|x| reads.contains(&x))
+    };
+
+    for instruction in analysis.instructions.values() {
+        if !live.contains_key(&instruction.address) && removable(instruction) {
+            removed.insert(instruction.address);
+            change(instruction.address, "removed unreachable instruction".to_string());
+        }
+    }
+
+    for jump in live.values().filter(|x| x.is_jump()) {
+        let target = jump.params[1];
+        match analysis.taken(image, jump) {
+            Some(false) if removable(jump) => {
+                removed.insert(jump.address);
+                change(jump.address, "removed jump that is never taken".to_string());
+            },
+            Some(true) if target == jump.next() as i64 && removable(jump) => {
+                removed.insert(jump.address);
+                change(jump.address, "removed jump to the next instruction".to_string());
+            },
+            Some(true) if jump.opcode != OP_JT || jump.modes[0] != 1 || jump.params[0] != 1 => {
+                rewritten.push((jump.address, vec![1105, 1, target]));
+                change(jump.address, format!("jump to @{} is always taken", target));
+            },
+            _ => ()
+        }
+    }
+
+    // Fold constant results computed before the first branch. Nothing can
+    // read the destination before then except earlier entry instructions.
+    let mut earlier_reads = HashSet::new();
+    for instruction in live.values() {
+        if instruction.is_jump() || instruction.opcode == OP_HALT {
+            break;
+        }
+        let dest = instruction.destination();
+        let a = || analysis.known(image, instruction, 0);
+        let b = || analysis.known(image, instruction, 1);
+        let value = match instruction.opcode {
+            OP_ADD => a().and_then(|a| b().and_then(|b| a.checked_add(b))),
+            OP_MUL => a().and_then(|a| b().and_then(|b| a.checked_mul(b))),
+            OP_CLT => a().and_then(|a| b().map(|b| (a < b) as i64)),
+            OP_CEQ => a().and_then(|a| b().map(|b| (a == b) as i64)),
+            _ => None
+        };
+        if let (Some(value), Some(dest)) = (value, dest) {
+            let in_image = dest >= 0 && (dest as usize) < image.len();
+            let unobserved = analysis.writers[&dest] == 1 && !earlier_reads.contains(&dest);
+            if in_image && unobserved && removable(instruction) {
+                removed.insert(instruction.address);
+                folded.push((dest as usize, value));
+                change(instruction.address, format!("folded constant {} into @{}", value, dest));
+                continue;
+            }
+        }
+        earlier_reads.extend(instruction.reads());
+    }
+
+    if changes.is_empty() {
+        return Ok(None);
+    }
+
+    let mut image = image.to_vec();
+    for (address, value) in folded {
+        image[address] = value;
+    }
+    for (address, cells) in rewritten {
+        image[address..address + cells.len()].copy_from_slice(&cells);
+    }
+
+    // Relocate: removed cells map to whatever follows them.
+    let mut deleted = vec![false; image.len()];
+    for address in &removed {
+        let instruction = &analysis.instructions[address];
+        for cell in &mut deleted[instruction.address..instruction.next()] {
+            *cell = true;
+        }
+    }
+    let mut relocation = vec![None; image.len()];
+    let mut forward = vec![0; image.len() + 1];
+    let mut next = 0;
+    for address in 0..image.len() {
+        forward[address] = next;
+        if !deleted[address] {
+            relocation[address] = Some(next);
+            next += 1;
+        }
+    }
+    forward[image.len()] = next;
+    let removed_count = image.len() - next;
+    let relocate = |address: i64| -> i64 {
+        if address < 0 {
+            address
+        }
+        else if address as usize > image.len() {
+            address - removed_count as i64
+        }
+        else {
+            forward[address as usize] as i64
+        }
+    };
+
+    let mut optimized: Vec<i64> = image.iter().enumerate()
+        .filter(|(address, _)| !deleted[*address])
+        .map(|(_, value)| *value)
+        .collect();
+    for address in live.keys().filter(|x| !removed.contains(x)) {
+        let instruction = Instruction::decode(&image, *address)?;
+        let new_address = forward[*address];
+        for (n, mode) in instruction.modes.iter().enumerate() {
+            let jump_target = instruction.is_jump() && n == 1;
+            if *mode == 0 || jump_target {
+                optimized[new_address + 1 + n] = relocate(instruction.params[n]);
+            }
+        }
+    }
+    Ok(Some((optimized, changes, relocation)))
+}
+
+/// Runs both images with the same inputs and compares their outputs.
+pub fn verify(original: &[i64], optimized: &[i64], inputs: &[i64]) -> Result<(), String> {
+    let run = |image: &[i64]| {
+        let mut vm = IntcodeBuilder::new()
+            .with_memory(image)
+            .with_inputs(inputs)
+            .build();
+        vm.run();
+        vm.outputs
+    };
+    let expected = run(original);
+    let actual = run(optimized);
+    if expected != actual {
+        return Err(format!("outputs differ: expected {:?}, got {:?}", expected, actual));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::intcode::Intcode;
+
+    fn assert_equivalent(original: &[i64], optimized: &[i64], inputs: &[&[i64]]) {
+        for input in inputs {
+            verify(original, optimized, input).unwrap();
+        }
+    }
+
+    #[test]
+    fn removes_jumps_with_constant_conditions() {
+        // jt 0 (never), jf @13 where @13 is never written (always), output 1
+        // is then skipped, output @14.
+        let program = [1105,0,11, 1006,13,9, 104,1, 99, 4,14, 99, 0, 0, 42];
+        let (optimized, report) = optimize(&program);
+        assert!(optimized.len() < program.len(), "{}", report);
+        assert_eq!(None, report.skipped);
+        assert_equivalent(&program, &optimized, &[&[]]);
+        let descriptions: Vec<&str> = report.changes.iter().map(|x| x.description.as_str()).collect();
+        assert!(descriptions.contains(&"removed jump that is never taken"));
+        assert!(descriptions.contains(&"removed unreachable instruction"));
+    }
+
+    #[test]
+    fn folds_entry_constants_into_image() {
+        // @20 = 2 * 3, @21 = @20 < 10, then branch on @21.
+        let program = [
+            1102,2,3,20, 7,20,22,21, 1005,21,16, 104,0, 1105,1,19, 4,20, 99, 99, 0, 0, 10
+        ];
+        let (optimized, report) = optimize(&program);
+        assert_equivalent(&program, &optimized, &[&[]]);
+        assert_eq!(2, report.changes.iter().filter(|x| x.description.starts_with("folded")).count(), "{}", report);
+        assert!(optimized.len() <= program.len() - 8 - 3, "{}", report);
+    }
+
+    #[test]
+    fn preserves_behaviour_of_day5_comparison_program() {
+        let program = Intcode::compile("3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99");
+        let (optimized, report) = optimize(&program);
+        assert_eq!(None, report.skipped);
+        assert!(!report.changes.is_empty());
+        assert_equivalent(&program, &optimized, &[&[7], &[8], &[9]]);
+    }
+
+    #[test]
+    fn skips_unsupported_programs() {
+        let self_modifying = [1,1,1,4,99,5,6,0,99];
+        let (optimized, report) = optimize(&self_modifying);
+        assert_eq!(&self_modifying[0..], &optimized[0..]);
+        assert!(report.skipped.unwrap().contains("self-modifying"));
+
+        let relative = [109,1,204,-1,99];
+        assert!(optimize(&relative).1.skipped.unwrap().contains("relative"));
+
+        let indirect = [105,1,4,99,3];
+        assert!(optimize(&indirect).1.skipped.unwrap().contains("indirect"));
+    }
+
+}
@@ -0,0 +1,151 @@
+//! Minimal JSON writer for machine-readable output.
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Null,
+    Integer(i64),
+    String(String),
+    Array(Vec<Value>),
+    /// Keys keep their insertion order.
+    Object(Vec<(String, Value)>)
+}
+
+impl Value {
+
+    pub fn object(entries: Vec<(&str, Value)>) -> Value {
+        Value::Object(entries.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    /// Pretty-prints with two-space indentation.
+    pub fn pretty(&self) -> String {
+        let mut out = String::new();
+        self.write(&mut out, 0);
+        out
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        let pad = |out: &mut String, indent: usize| out.push_str(&"  ".repeat(indent));
+        match self {
+            Value::Array(items) if !items.is_empty() => {
+                out.push_str("[\n");
+                for (index, item) in items.iter().enumerate() {
+                    pad(out, indent + 1);
+                    item.write(out, indent + 1);
+                    out.push_str(if index + 1 < items.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push(']');
+            },
+            Value::Object(entries) if !entries.is_empty() => {
+                out.push_str("{\n");
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    pad(out, indent + 1);
+                    out.push_str(&format!("{}: ", Value::String(key.clone())));
+                    value.write(out, indent + 1);
+                    out.push_str(if index + 1 < entries.len() { ",\n" } else { "\n" });
+                }
+                pad(out, indent);
+                out.push('}');
+            },
+            value => out.push_str(&value.to_string())
+        }
+    }
+
+}
+
+impl From<&str> for Value {
+    fn from(value: &str) -> Value {
+        Value::String(value.to_string())
+    }
+}
+
+impl From<String> for Value {
+    fn from(value: String) -> Value {
+        Value::String(value)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(value: usize) -> Value {
+        Value::Integer(value as i64)
+    }
+}
+
+impl<T: Into<Value>> From<Option<T>> for Value {
+    fn from(value: Option<T>) -> Value {
+        value.map(|x| x.into()).unwrap_or(Value::Null)
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::String(value) => {
+                write!(f, "\"")?;
+                for c in value.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\r' => write!(f, "\\r")?,
+                        '\t' => write!(f, "\\t")?,
+                        c if (c as u32) < 0x20 => write!(f, "\\u{:04x}", c as u32)?,
+                        c => write!(f, "{}", c)?
+                    }
+                }
+                write!(f, "\"")
+            },
+            Value::Array(items) => {
+                write!(f, "[")?;
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", item)?;
+                }
+                write!(f, "]")
+            },
+            Value::Object(entries) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in entries.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", Value::String(key.clone()), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn writes_compact_json() {
+        let value = Value::object(vec![
+            ("day", 9usize.into()),
+            ("answer", "a \"quoted\"\nline\u{1}".into()),
+            ("missing", Value::from(None::<usize>)),
+            ("parts", Value::Array(vec![Value::Integer(-1), Value::Array(Vec::new())]))
+        ]);
+        assert_eq!(r#"{"day":9,"answer":"a \"quoted\"\nline\u0001","missing":null,"parts":[-1,[]]}"#, value.to_string());
+    }
+
+    #[test]
+    fn pretty_prints() {
+        let value = Value::object(vec![
+            ("days", Value::Array(vec![Value::object(vec![("day", 1usize.into())])])),
+            ("empty", Value::Array(Vec::new()))
+        ]);
+        assert_eq!("{\n  \"days\": [\n    {\n      \"day\": 1\n    }\n  ],\n  \"empty\": []\n}", value.pretty());
+    }
+
+}
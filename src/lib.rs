@@ -0,0 +1,39 @@
+//! Advent of Code solutions and the code they share.
+//!
+//! - [`intcode`]: the Intcode virtual machine, with its compiler, optimizer and
+//!   coverage tooling
+//! - [`solver`]: the [`Solver`](solver::Solver) trait every day implements, and
+//!   the [`Answer`](solver::Answer) it produces
+//! - [`error`]: the [`Error`](error::Error) solvers return, with context
+//! - [`parse`]: helpers for parsing inputs with line and column errors
+//! - [`registry`]: every day's solver, keyed by `(year, day)`
+//! - [`input`], [`answers`], [`examples`]: puzzle inputs, recorded answers and
+//!   example inputs on disk
+//! - [`timing`], [`pool`], [`bench`]: measuring and running solvers
+//! - [`history`]: each part's last recorded result, for `aoc status`
+//! - [`client`], [`http`]: fetching inputs from and submitting answers to an
+//!   AoC-compatible site
+//! - [`config`]: user and project defaults for the runner
+//! - [`toml`], [`json`]: the small file formats the runner reads and writes
+//!
+//! The `aoc` binary is a thin command-line runner over this library.
+
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod config;
+pub mod error;
+pub mod examples;
+pub mod history;
+pub mod http;
+pub mod input;
+pub mod intcode;
+pub mod json;
+pub mod parse;
+pub mod pool;
+pub mod registry;
+pub mod solver;
+#[cfg(test)]
+mod testutil;
+pub mod timing;
+pub mod toml;
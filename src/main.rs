@@ -1,37 +1,443 @@
-mod intcode;
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod day8;
-mod day9;
-
-type Solver = fn() -> ();
+mod cli;
+mod repl;
+mod report;
+mod scaffold;
+mod status;
+#[cfg(test)]
+#[path = "testutil.rs"]
+mod testutil;
+mod watch;
+
+use aoc::answers::{self, Answers, Rejection, Rejections, Verdict};
+use aoc::client::{Client, Outcome};
+use aoc::config::Config;
+use aoc::history::{self, History, Record, Status};
+use aoc::input::{self, Fetched, Source};
+use aoc::intcode::IntcodeBuilder;
+use aoc::registry::{self, Day};
+use aoc::solver::{Answer, Run};
+use aoc::{examples, parse, pool, timing};
+use cli::{Command, Format, Selection};
+use report::Report;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
 
 fn main() {
-    println!("advent of code 2019");
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let (config, config_error) = match Config::load() {
+        Ok(config) => (config, None),
+        Err(message) => (Config::default(), Some(message))
+    };
+    let command = match cli::parse(&args, &config) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            eprintln!();
+            eprintln!("{}", cli::USAGE);
+            process::exit(2);
+        }
+    };
+    // A broken config file only stops the commands that depend on it.
+    if let Some(message) = config_error {
+        match command {
+            Command::Help | Command::List | Command::Intcode { .. } => eprintln!("warning: ignoring config: {}", message),
+            _ => {
+                eprintln!("error: {}", message);
+                process::exit(2);
+            }
+        }
+    }
+
+    match command {
+        Command::Help => println!("{}", cli::USAGE),
+        Command::List => list(config.inputs.as_deref().unwrap_or(Path::new(input::DEFAULT_DIR))),
+        Command::Watch(selection) => {
+            let year = selection.year.unwrap_or_else(default_year);
+            let day = *selection.days.as_ref().unwrap().start();
+            if let Err(message) = watch::watch(&selection, year, day) {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        },
+        Command::NewDay { year, day, inputs } => {
+            let year = year.unwrap_or_else(default_year);
+            // Both files belong in this crate's tree, wherever it's run from.
+            let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+            let src = root.join("src");
+            let inputs = inputs.unwrap_or_else(|| root.join(input::DEFAULT_DIR));
+            match scaffold::new_day(&src, &inputs, year, day) {
+                Ok(created) => {
+                    for path in created {
+                        println!("created {}", path.display());
+                    }
+                },
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+            }
+        },
+        Command::Intcode { program, mode, memory } => {
+            let code = fs::read_to_string(&program)
+                .map_err(|x| format!("failed to read {}: {}", program.display(), x))
+                .and_then(|text| parse::comma_list::<i64>(&text).map_err(|x| format!("{}: {}", program.display(), x)));
+            let mut vm = match code {
+                Ok(code) => IntcodeBuilder::new()
+                    .with_memory_size(memory.unwrap_or(repl::DEFAULT_MEMORY).max(code.len()))
+                    .with_memory(&code)
+                    .build(),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+            };
+            if let Err(x) = repl::repl(&mut vm, mode, io::stdin().lock(), &mut io::stdout()) {
+                eprintln!("error: {}", x);
+                process::exit(1);
+            }
+            if !vm.halted {
+                eprintln!();
+                eprintln!("end of input; program stopped at address {}", vm.pc);
+            }
+        },
+        Command::Status { year, answers } => {
+            let year = year.unwrap_or_else(default_year);
+            let answers = answers.unwrap_or_else(|| PathBuf::from(answers::DEFAULT_DIR));
+            if let Err(message) = status(year, &answers) {
+                eprintln!("error: {}", message);
+                process::exit(1);
+            }
+        },
+        Command::Fetch { year, day, url } => {
+            let year = year.unwrap_or_else(default_year);
+            let fetched = input::cache_dir()
+                .ok_or_else(|| "no cache directory; set XDG_CACHE_HOME or HOME".to_string())
+                .and_then(|cache| input::fetch(&Client::configure(url.as_deref(), &config)?, &cache, year, day));
+            match fetched {
+                Ok(Fetched::Downloaded(path)) => println!("downloaded {} day {} to {}", year, day, path.display()),
+                Ok(Fetched::Cached(path)) => println!("{} day {} is already cached at {}", year, day, path.display()),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+            }
+        },
+        Command::Submit { year, day, part, answer, url, inputs, answers } => {
+            let year = year.unwrap_or_else(default_year);
+            let inputs = inputs.unwrap_or_else(|| PathBuf::from(input::DEFAULT_DIR));
+            let answers = answers.unwrap_or_else(|| PathBuf::from(answers::DEFAULT_DIR));
+            let client = match Client::configure(url.as_deref(), &config) {
+                Ok(client) => client,
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+            };
+            match submit(&client, year, day, part, answer, &inputs, &answers) {
+                Ok(true) => {},
+                Ok(false) => process::exit(1),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    process::exit(1);
+                }
+            }
+        },
+        Command::Run(selection) => {
+            if !run(&selection, false) {
+                process::exit(1);
+            }
+        },
+        Command::Verify(selection) => {
+            if !run(&selection, true) {
+                process::exit(1);
+            }
+        }
+    }
+}
+
+/// The latest year with a registered day.
+fn default_year() -> usize {
+    *registry::years(&registry::registry()).last().unwrap()
+}
+
+fn list(inputs: &Path) {
+    let registry = registry::registry();
+    println!("available days:");
+    for year in registry::years(&registry) {
+        println!("  {}:", year);
+        for ((_, day), entry) in registry.iter().filter(|((x, _), _)| *x == year) {
+            let parts: Vec<String> = (1..=entry.parts).map(|x| x.to_string()).collect();
+            println!("    {:>2}  parts: {}", day, parts.join(", "));
+        }
+    }
+    let examples = examples::names(inputs);
+    if !examples.is_empty() {
+        println!("examples (inputs/YEAR/examples/DAY):");
+        for (year, day, names) in examples {
+            println!("  {}/{}: {}", year, day, names.join(", "));
+        }
+    }
+}
+
+fn status(year: usize, answers: &Path) -> Result<(), String> {
+    let history = match history::dir() {
+        Some(dir) => History::load(&dir, year)?,
+        None => History::default()
+    };
+    let mut stars = BTreeMap::new();
+    for day in 1..=25 {
+        let recorded = Answers::load(answers, year, day)?;
+        stars.insert(day, (1..=2).filter(|x| recorded.get(*x).is_some()).count());
+    }
+    let progress = status::Progress {
+        year,
+        implemented: registry::registry().keys().filter(|(x, _)| *x == year).map(|(_, day)| *day).collect(),
+        history: &history,
+        stars
+    };
+    println!("{}", status::table(&progress, history::now()));
+    Ok(())
+}
+
+/// Records each part's result for `aoc status`. Answers that can't be loaded
+/// are warned about, and the parts recorded as unchecked.
+fn record(year: usize, reports: &[Report], selection: &Selection) -> Result<(), String> {
+    let dir = match history::dir() {
+        Some(dir) => dir,
+        None => return Ok(())
+    };
+    let mut history = History::load(&dir, year)?;
+    let at = history::now();
+    let answers_dir = selection.answers.as_deref().unwrap_or_else(|| Path::new(answers::DEFAULT_DIR));
+    for (day, result) in reports {
+        let run = match result {
+            Ok((run, _)) => run,
+            Err(_) => continue
+        };
+        let answers = Answers::load(answers_dir, year, day.number).unwrap_or_else(|message| {
+            eprintln!("warning: {}; recording day {} as unchecked", message, day.number);
+            Answers::default()
+        });
+        for part_run in &run.parts {
+            let status = match &part_run.outcome {
+                Err(_) => Status::Failed,
+                Ok(Answer::Unsolved) => Status::Unsolved,
+                Ok(answer) => match answers.check(part_run.part, answer) {
+                    Verdict::Correct => Status::Correct,
+                    Verdict::Wrong(_) => Status::Wrong,
+                    Verdict::Unrecorded => Status::Unchecked
+                }
+            };
+            let elapsed = match status {
+                Status::Failed | Status::Unsolved => None,
+                _ => part_run.timing.median()
+            };
+            history.record(day.number, part_run.part, Record {
+                status,
+                elapsed,
+                at
+            });
+        }
+    }
+    history.save(&dir, year)
+}
+
+/// Submits an answer for one part, solving it unless `answer` is given.
+/// Returns true if the site accepted it, or it was already recorded.
+fn submit(client: &Client, year: usize, day: usize, part: usize, answer: Option<String>, inputs: &Path, dir: &Path) -> Result<bool, String> {
+    let answer = match answer {
+        Some(answer) => answer,
+        None => {
+            let entry = registry::registry().get(&(year, day)).copied()
+                .ok_or_else(|| format!("day {} of {} isn't implemented; see `aoc list`", day, year))?;
+            if part > entry.parts {
+                return Err(format!("part {} of day {} isn't implemented", part, day));
+            }
+            let input = Source::Directory(inputs.to_path_buf()).load(year, day)?;
+            let run = (entry.solve)(&input, &[part], 1);
+            match &run.parts[0].outcome {
+                Ok(Answer::Unsolved) => return Err(format!("part {} is unsolved", part)),
+                Ok(Answer::Image(image)) => {
+                    println!("{}", image);
+                    return Err("read the letters off the image and pass them with --answer".to_string());
+                },
+                Ok(answer) => answer.to_string(),
+                Err(message) => return Err(format!("part {} failed: {}", part, message))
+            }
+        }
+    };
+
+    match Answers::load(dir, year, day)?.get(part) {
+        Some(recorded) if recorded == answer => {
+            println!("{} is already recorded as the answer to day {} part {}", answer, day, part);
+            return Ok(true);
+        },
+        Some(recorded) => return Err(format!("day {} part {} already has the recorded answer {}", day, part, recorded)),
+        None => {}
+    }
+    let mut rejections = Rejections::load(dir, year, day)?;
+    if let Some(reason) = rejections.check(part, &answer) {
+        return Err(format!("not submitting: {}", reason));
+    }
+
+    println!("submitting {} for {} day {} part {}", answer, year, day, part);
+    let outcome = client.submit(year, day, part, &answer)?;
+    println!("{}", outcome);
+    let rejection = match outcome {
+        Outcome::Correct => {
+            Answers::record(dir, year, day, part, &answer)?;
+            return Ok(true);
+        },
+        Outcome::Wrong => Rejection::Wrong,
+        Outcome::TooHigh => Rejection::TooHigh,
+        Outcome::TooLow => Rejection::TooLow,
+        _ => return Ok(false)
+    };
+    rejections.add(part, rejection, &answer);
+    rejections.save(dir, year, day)?;
+    Ok(false)
+}
+
+/// Loads the input (and recorded answers, if `check` is set) for one day and
+/// solves the selected parts. The answers directory holds the answers for the
+/// default inputs directory; another inputs directory is only checked against
+/// an explicit `--answers DIR`, and other inputs have none to check against.
+fn execute(day: &Day, selection: &Selection, check: bool) -> Result<(Run, Answers), String> {
+    let input = selection.input.load(day.year, day.number)?;
+    let answers = match &selection.input {
+        Source::Example { dir, name } if check => Answers::load_file(&examples::path(dir, day.year, day.number, name, "toml"))?,
+        Source::Directory(_) if check => match &selection.answers {
+            Some(dir) => Answers::load(dir, day.year, day.number)?,
+            None if selection.input == Source::default() => Answers::load(Path::new(answers::DEFAULT_DIR), day.year, day.number)?,
+            None => Answers::default()
+        },
+        _ => Answers::default()
+    };
+    let parts: Vec<usize> = (1..=day.parts).filter(|x| selection.includes_part(*x)).collect();
+    let run = (day.solve)(&input, &parts, selection.repeat.unwrap_or(1));
+    Ok((run, answers))
+}
+
+/// Runs the selected parts, returning false if an input was missing or a part
+/// panicked. When verifying, answers that differ from the recorded ones also
+/// count as failures. Days may run concurrently but are reported in order.
+fn run(selection: &Selection, verify: bool) -> bool {
+    let registry = registry::registry();
+    let year = selection.year.unwrap_or_else(default_year);
+    let days: Vec<Day> = registry.into_iter()
+        .filter(|((x, day), _)| *x == year && selection.includes_day(*day))
+        .map(|((year, number), entry)| Day {
+            year,
+            number,
+            solve: entry.solve,
+            parts: entry.parts
+        })
+        .collect();
+    if days.is_empty() {
+        eprintln!("error: no days of {} match the selection; see `aoc list`", year);
+        return false;
+    }
+    if let Some(part) = selection.part {
+        if days.iter().all(|day| day.parts < part) {
+            eprintln!("error: part {} is not implemented for the selected days", part);
+            return false;
+        }
+    }
+
+    let jobs = match selection.jobs {
+        Some(0) => pool::available_threads(),
+        Some(jobs) => jobs,
+        None => 1
+    };
+    // Only runs against the default inputs directory are recorded for
+    // `aoc status`; any other directory may hold someone else's inputs.
+    let recording = selection.input == Source::default();
+    let check = verify || selection.format == Format::Json;
+    let reports: Vec<Report> = days.iter()
+        .zip(pool::map(&days, jobs, |day| execute(day, selection, check)))
+        .map(|(day, result)| (day, result.and_then(|x| x)))
+        .collect();
+
+    match selection.format {
+        Format::Text => print_text(year, &reports, selection, verify),
+        Format::Json => println!("{}", report::json(year, &reports, selection).pretty())
+    }
+    if recording {
+        if let Err(message) = record(year, &reports, selection) {
+            eprintln!("warning: failed to record run history: {}", message);
+        }
+    }
+
+    reports.iter().all(|(_, result)| match result {
+        Ok((run, answers)) => run.parts.iter().all(|x| match &x.outcome {
+            Ok(answer) => !verify || !matches!(answers.check(x.part, answer), Verdict::Wrong(_)),
+            Err(_) => false
+        }),
+        Err(_) => false
+    })
+}
+
+fn print_text(year: usize, reports: &[Report], selection: &Selection, verify: bool) {
+    println!("advent of code {}", year);
     println!("chris hutchinson <github.com/chutchinson>");
     println!();
 
-    let days: Vec<Solver> = vec![
-        day1::solve,
-        day2::solve,
-        day3::solve,
-        day4::solve,
-        day5::solve,
-        day6::solve,
-        day7::solve,
-        day8::solve,
-        day9::solve
-    ];
-
-    for (index, solve) in days.iter().enumerate() {
-        println!("day {} ", index + 1);
+    let mut verdicts = Vec::new();
+    for (day, result) in reports {
+        println!("day {} ", day.number);
         println!("---");
-        solve();
+        let (run, answers) = match result {
+            Ok(result) => result,
+            Err(message) => {
+                eprintln!("day {} skipped: {}", day.number, message);
+                println!("---");
+                continue;
+            }
+        };
+        for part_run in &run.parts {
+            let part = part_run.part;
+            match &part_run.outcome {
+                Ok(answer) => {
+                    let verdict = if verify { Some(answers.check(part, answer)) } else { None };
+                    let status = match &verdict {
+                        None => String::new(),
+                        Some(Verdict::Correct) => " [ok]".to_string(),
+                        Some(Verdict::Wrong(expected)) if expected.contains('\n') => " [wrong]".to_string(),
+                        Some(Verdict::Wrong(expected)) => format!(" [wrong, expected {}]", expected),
+                        Some(Verdict::Unrecorded) => " [no recorded answer]".to_string()
+                    };
+                    match answer {
+                        Answer::Image(image) => println!("part {}:{}\n{}", part, status, image),
+                        answer => println!("part {}: {}{}", part, answer, status)
+                    }
+                    if let Some(Verdict::Wrong(expected)) = &verdict {
+                        if expected.contains('\n') {
+                            println!("expected:\n{}", expected);
+                        }
+                    }
+                    verdicts.extend(verdict);
+                },
+                Err(message) => eprintln!("day {} part {} failed: {}", day.number, part, message)
+            }
+        }
         println!("---");
     }
-}
\ No newline at end of file
+    if selection.time || selection.repeat.is_some() {
+        let runs: Vec<(usize, &Run)> = reports.iter()
+            .filter_map(|(day, result)| result.as_ref().ok().map(|(run, _)| (day.number, run)))
+            .collect();
+        println!();
+        println!("{}", timing::summary(&runs, selection.repeat.unwrap_or(1) > 1));
+    }
+    if verify {
+        let count = |f: fn(&Verdict) -> bool| verdicts.iter().filter(|x| f(x)).count();
+        println!();
+        println!("{} correct, {} wrong, {} unrecorded",
+            count(|x| *x == Verdict::Correct),
+            count(|x| matches!(x, Verdict::Wrong(_))),
+            count(|x| *x == Verdict::Unrecorded));
+    }
+}
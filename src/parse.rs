@@ -0,0 +1,219 @@
+//! Helpers for parsing puzzle inputs. Errors carry the line and column of the
+//! offending text, so a malformed input is reported rather than panicking:
+//!
+//! ```
+//! use aoc::parse;
+//!
+//! assert_eq!(Ok(vec![12, 14]), parse::lines::<i64>("12\n14\n"));
+//! let error = parse::comma_list::<i64>("3,9,x,99").unwrap_err();
+//! assert_eq!("line 1, column 5: can't parse `x`: invalid digit found in string", error.to_string());
+//! ```
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Lines and columns count from 1; columns are in bytes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub line: usize,
+    pub column: usize,
+    pub message: String
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A piece of the input that knows where it came from.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Field<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize
+}
+
+impl<'a> Field<'a> {
+
+    pub fn error(&self, message: &str) -> Error {
+        Error {
+            line: self.line,
+            column: self.column,
+            message: message.to_string()
+        }
+    }
+
+    pub fn parse<T: FromStr>(&self) -> Result<T> where T::Err: fmt::Display {
+        self.text.parse().map_err(|x| self.error(&format!("can't parse `{}`: {}", self.text, x)))
+    }
+
+    /// The field from byte `start` on.
+    pub fn skip(&self, start: usize) -> Field<'a> {
+        Field {
+            text: &self.text[start..],
+            line: self.line,
+            column: self.column + start
+        }
+    }
+
+    /// Splits on `delimiter`, trimming whitespace around each piece.
+    pub fn split(&self, delimiter: &str) -> Vec<Field<'a>> {
+        let mut fields = Vec::new();
+        let mut offset = 0;
+        for piece in self.text.split(delimiter) {
+            let trimmed = piece.trim_start();
+            fields.push(Field {
+                text: trimmed.trim_end(),
+                line: self.line,
+                column: self.column + offset + piece.len() - trimmed.len()
+            });
+            offset += piece.len() + delimiter.len();
+        }
+        fields
+    }
+
+}
+
+/// The non-blank lines of `input`, with surrounding whitespace trimmed.
+pub fn fields(input: &str) -> impl Iterator<Item = Field<'_>> {
+    input.lines()
+        .enumerate()
+        .map(|(index, text)| {
+            let trimmed = text.trim_start();
+            Field {
+                text: trimmed.trim_end(),
+                line: index + 1,
+                column: 1 + text.len() - trimmed.len()
+            }
+        })
+        .filter(|x| !x.text.is_empty())
+}
+
+/// One value per line, e.g. a list of integers.
+pub fn lines<T: FromStr>(input: &str) -> Result<Vec<T>> where T::Err: fmt::Display {
+    fields(input).map(|x| x.parse()).collect()
+}
+
+/// A single line of comma-separated values, e.g. an Intcode program.
+pub fn comma_list<T: FromStr>(input: &str) -> Result<Vec<T>> where T::Err: fmt::Display {
+    let lines: Vec<Field> = fields(input).collect();
+    match lines.as_slice() {
+        [line] => line.split(",").iter().map(|x| x.parse()).collect(),
+        [] => Err(Error {
+            line: 1,
+            column: 1,
+            message: "expected a comma-separated list, found nothing".to_string()
+        }),
+        [_, extra, ..] => Err(extra.error("expected a single line"))
+    }
+}
+
+/// One record per line, made of exactly `count` fields separated by
+/// `delimiter`, and built by `f`.
+pub fn records<'a, T, F>(input: &'a str, delimiter: &str, count: usize, f: F) -> Result<Vec<T>>
+    where F: Fn(&[Field<'a>]) -> Result<T> {
+    fields(input)
+        .map(|line| {
+            let fields = line.split(delimiter);
+            if fields.len() != count {
+                return Err(line.error(&format!("expected {} fields separated by `{}`, found {}", count, delimiter, fields.len())));
+            }
+            f(&fields)
+        })
+        .collect()
+}
+
+/// A rectangular grid, stored row by row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Grid<T> {
+    pub width: usize,
+    pub height: usize,
+    pub cells: Vec<T>
+}
+
+impl<T> Grid<T> {
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        if x < self.width && y < self.height { self.cells.get(y * self.width + x) } else { None }
+    }
+
+}
+
+/// A grid of cells converted by `f`, one per `char`; every row must be the
+/// same width.
+pub fn grid_of<T, F>(input: &str, f: F) -> Result<Grid<T>>
+    where F: Fn(char, &Field) -> Result<T> {
+    let mut grid = Grid {
+        width: 0,
+        height: 0,
+        cells: Vec::new()
+    };
+    for line in fields(input) {
+        let width = line.text.chars().count();
+        if grid.height == 0 {
+            grid.width = width;
+        }
+        else if width != grid.width {
+            return Err(line.error(&format!("expected a row of width {}, found {}", grid.width, width)));
+        }
+        for (index, c) in line.text.char_indices() {
+            grid.cells.push(f(c, &line.skip(index))?);
+        }
+        grid.height += 1;
+    }
+    Ok(grid)
+}
+
+pub fn grid(input: &str) -> Result<Grid<char>> {
+    grid_of(input, |c, _| Ok(c))
+}
+
+pub fn digits(input: &str) -> Result<Grid<u8>> {
+    grid_of(input, |c, field| {
+        c.to_digit(10).map(|x| x as u8).ok_or_else(|| field.error(&format!("expected a digit, found `{}`", c)))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parses_lines_and_lists() {
+        assert_eq!(Ok(vec![1.5, -2.0]), lines::<f32>("1.5\n\n  -2\n"));
+        assert_eq!(Ok(vec![1, 2, 3]), comma_list::<i64>("1, 2,3\n"));
+        let error = lines::<i64>("1\n2\n3a").unwrap_err();
+        assert_eq!((3, 1), (error.line, error.column));
+        assert_eq!("expected a single line", comma_list::<i64>("1,2\n3").unwrap_err().message);
+        assert!(comma_list::<i64>("\n").is_err());
+    }
+
+    #[test]
+    fn splits_records_with_positions() {
+        let orbits = records("COM)B\nB)C", ")", 2, |x| Ok((x[0].text, x[1].text)));
+        assert_eq!(Ok(vec![("COM", "B"), ("B", "C")]), orbits);
+        let error = records("COM)B\nB", ")", 2, |_| Ok(())).unwrap_err();
+        assert_eq!("line 2, column 1: expected 2 fields separated by `)`, found 1", error.to_string());
+        let error = records("R8,U5 ,Lx", ",", 3, |x| x.iter().map(|x| x.skip(1).parse::<usize>()).collect::<Result<Vec<_>>>()).unwrap_err();
+        assert_eq!((1, 9), (error.line, error.column));
+    }
+
+    #[test]
+    fn parses_grids() {
+        let grid = grid("#.\n.#\n").unwrap();
+        assert_eq!((2, 2), (grid.width, grid.height));
+        assert_eq!(Some(&'#'), grid.get(1, 1));
+        assert_eq!(None, grid.get(2, 0));
+        assert_eq!(vec![1, 2, 3, 4], digits("12\n34").unwrap().cells);
+        let grid = super::grid("█.\n.█\n").unwrap();
+        assert_eq!((2, 2, 4), (grid.width, grid.height, grid.cells.len()));
+        assert_eq!(Some(&'█'), grid.get(1, 1));
+        assert_eq!("line 2, column 2: expected a digit, found `x`", digits("12\n3x").unwrap_err().to_string());
+        assert_eq!("line 2, column 1: expected a row of width 2, found 3", digits("12\n345").unwrap_err().to_string());
+    }
+
+}
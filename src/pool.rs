@@ -0,0 +1,65 @@
+//! A small scoped thread pool for solving days concurrently.
+
+use crate::solver::panic_message;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+/// Number of threads to use when the user asks for one per CPU.
+pub fn available_threads() -> usize {
+    thread::available_parallelism().map(|x| x.get()).unwrap_or(1)
+}
+
+/// Applies `f` to every item on up to `threads` worker threads, returning the
+/// results in item order. A panic in `f` is returned as that item's `Err`
+/// instead of taking down the other workers.
+pub fn map<T, R, F>(items: &[T], threads: usize, f: F) -> Vec<Result<R, String>>
+    where T: Sync, R: Send, F: Fn(&T) -> R + Sync {
+    let next = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<R, String>>>> = Mutex::new(items.iter().map(|_| None).collect());
+    let worker = || loop {
+        let index = next.fetch_add(1, Ordering::SeqCst);
+        if index >= items.len() {
+            break;
+        }
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&items[index]))).map_err(panic_message);
+        results.lock().unwrap()[index] = Some(result);
+    };
+    thread::scope(|scope| {
+        for _ in 0..threads.clamp(1, items.len().max(1)) {
+            scope.spawn(worker);
+        }
+    });
+    results.into_inner().unwrap().into_iter().map(|x| x.unwrap()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn preserves_item_order() {
+        let items: Vec<u64> = (0..16).collect();
+        let results = map(&items, 4, |x| {
+            thread::sleep(Duration::from_millis(16 - x));
+            x * x
+        });
+        let expected: Vec<Result<u64, String>> = items.iter().map(|x| Ok(x * x)).collect();
+        assert_eq!(expected, results);
+    }
+
+    #[test]
+    fn contains_panics() {
+        let results = map(&[1, 2, 3], 2, |x| {
+            if *x == 2 {
+                panic!("day {} exploded", x);
+            }
+            *x
+        });
+        assert_eq!(vec![Ok(1), Err("day 2 exploded".to_string()), Ok(3)], results);
+    }
+
+}
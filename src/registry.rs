@@ -0,0 +1,58 @@
+//! Every day's solver, keyed by `(year, day)`.
+
+use crate::solver::Run;
+use std::collections::BTreeMap;
+
+// Day modules (`registry::yYYYY::dayN`) and `register`, generated by build.rs
+// from the files under `src/yYYYY/`.
+include!(concat!(env!("OUT_DIR"), "/registry.rs"));
+
+/// Solves one day's input for the given parts, repeating each step `repeat`
+/// times; see `solver::solve`.
+pub type Solve = fn(&str, &[usize], usize) -> Run;
+
+/// A registered solver and the number of parts it implements; see
+/// `Solver::PARTS`.
+#[derive(Clone, Copy)]
+pub struct Entry {
+    pub solve: Solve,
+    pub parts: usize
+}
+
+/// Every solver, keyed and ordered by `(year, day)`.
+pub type Registry = BTreeMap<(usize, usize), Entry>;
+
+/// A registered day, resolved from the registry.
+pub struct Day {
+    pub year: usize,
+    pub number: usize,
+    pub solve: Solve,
+    pub parts: usize
+}
+
+pub fn registry() -> Registry {
+    let mut registry = Registry::new();
+    register(&mut registry);
+    registry
+}
+
+pub fn years(registry: &Registry) -> Vec<usize> {
+    let mut years: Vec<usize> = registry.keys().map(|(year, _)| *year).collect();
+    years.dedup();
+    years
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn registers_days_by_year() {
+        let registry = registry();
+        assert!(years(&registry).contains(&2019));
+        assert!(registry.contains_key(&(2019, 9)));
+        assert!(!registry.contains_key(&(2019, 26)));
+    }
+
+}
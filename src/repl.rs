@@ -0,0 +1,186 @@
+//! Runs an Intcode program interactively: outputs are printed as they arrive
+//! and the user is prompted whenever the program needs input.
+
+use aoc::intcode::Intcode;
+use aoc::solver::panic_message;
+use std::cell::Cell;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+
+/// Memory size used unless `--memory` is given; enough for every 2019 program.
+pub const DEFAULT_MEMORY: usize = 65536;
+const PROMPT: &str = "> ";
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Mode {
+    /// Integers in and out, one output per line.
+    #[default]
+    Numeric,
+    /// Lines of text in, characters out; values outside ASCII are printed as
+    /// numbers, as puzzles use them for their final answers.
+    Ascii
+}
+
+/// Converts a line typed by the user into input values.
+fn inputs(mode: Mode, line: &str) -> Result<Vec<i64>, String> {
+    match mode {
+        Mode::Ascii => Ok(line.chars().map(|c| c as i64).chain(Some(10)).collect()),
+        Mode::Numeric => line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|x| !x.is_empty())
+            .map(|x| x.parse().map_err(|_| format!("invalid input `{}`, expected integers", x)))
+            .collect()
+    }
+}
+
+fn print(mode: Mode, value: i64, output: &mut impl Write) -> io::Result<()> {
+    match mode {
+        Mode::Ascii if (0..128).contains(&value) => write!(output, "{}", value as u8 as char),
+        Mode::Ascii => writeln!(output, "\n{}", value),
+        Mode::Numeric => writeln!(output, "{}", value)
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The program hit a bad opcode, parameter mode or address.
+    Fault {
+        address: usize,
+        message: String
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => write!(f, "{}", error),
+            Error::Fault { address, message } => write!(f, "fault at address {}: {}", address, message)
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::Io(error)
+    }
+}
+
+thread_local! {
+    /// Set while this thread runs a VM step, whose panics become faults.
+    static STEPPING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Executes one instruction, returning the message of any panic. The panic
+/// hook is wrapped, once, to stay quiet for these panics only.
+fn step(vm: &mut Intcode) -> Result<(), String> {
+    static QUIET: Once = Once::new();
+    QUIET.call_once(|| {
+        let previous = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !STEPPING.with(|x| x.get()) {
+                previous(info);
+            }
+        }));
+    });
+    STEPPING.with(|x| x.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| vm.cycle()));
+    STEPPING.with(|x| x.set(false));
+    result.map_err(panic_message)
+}
+
+/// Runs `vm` until it halts or `input` runs out while the program is waiting
+/// for a value. A fault (e.g. a bad opcode or address) stops the session with
+/// [`Error::Fault`], leaving the machine where it failed.
+pub fn repl<R: BufRead, W: Write>(vm: &mut Intcode, mode: Mode, mut input: R, output: &mut W) -> Result<(), Error> {
+    let mut printed = vm.outputs.len();
+    while !vm.halted {
+        if vm.awaiting_input() {
+            output.write_all(PROMPT.as_bytes())?;
+            output.flush()?;
+            let mut line = String::new();
+            if input.read_line(&mut line)? == 0 {
+                return Ok(());
+            }
+            match inputs(mode, line.trim_end_matches(['\r', '\n'])) {
+                Ok(values) => vm.inputs.extend(values),
+                Err(message) => writeln!(output, "{}", message)?
+            }
+            continue;
+        }
+        let address = vm.pc;
+        let stepped = step(vm);
+        for value in &vm.outputs[printed..] {
+            print(mode, *value, output)?;
+        }
+        printed = vm.outputs.len();
+        if let Err(message) = stepped {
+            output.flush()?;
+            return Err(Error::Fault {
+                address,
+                message
+            });
+        }
+    }
+    Ok(output.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use aoc::intcode::IntcodeBuilder;
+
+    fn session(program: &str, mode: Mode, typed: &str) -> (Intcode, String) {
+        let mut vm = IntcodeBuilder::new()
+            .with_program(program)
+            .with_memory_size(64)
+            .build();
+        let mut output = Vec::new();
+        repl(&mut vm, mode, typed.as_bytes(), &mut output).unwrap();
+        (vm, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn prompts_for_numeric_input() {
+        // Echoes every input until it reads 0.
+        let program = "3,20,4,20,1005,20,0,99";
+        let (vm, output) = session(program, Mode::Numeric, "7\nseven\n8, 9\n0\n");
+        assert!(vm.halted);
+        assert_eq!("> 7\n> invalid input `seven`, expected integers\n> 8\n9\n> 0\n", output);
+    }
+
+    #[test]
+    fn stops_when_input_runs_out() {
+        let (vm, output) = session("3,20,4,20,1105,1,0", Mode::Numeric, "5\n");
+        assert!(!vm.halted && vm.awaiting_input());
+        assert_eq!("> 5\n> ", output);
+    }
+
+    #[test]
+    fn reads_and_writes_ascii() {
+        // Echoes every character until a newline, then outputs 1000.
+        let program = "3,30,4,30,1008,30,10,31,1006,31,0,104,1000,99";
+        let (vm, output) = session(program, Mode::Ascii, "hi\n");
+        assert!(vm.halted);
+        assert_eq!("> hi\n\n1000\n", output);
+    }
+
+    #[test]
+    fn stops_at_faults() {
+        // Outputs 7, then writes to address 100, beyond the 64 cells of memory.
+        let mut vm = IntcodeBuilder::new()
+            .with_program("104,7,1,0,0,100,99")
+            .with_memory_size(64)
+            .build();
+        let mut output = Vec::new();
+        let error = repl(&mut vm, Mode::Numeric, "1\n".as_bytes(), &mut output).unwrap_err();
+        assert!(matches!(error, Error::Fault { address: 2, .. }), "{:?}", error);
+        assert!(error.to_string().starts_with("fault at address 2: "), "{}", error);
+        assert!(!vm.halted);
+        assert_eq!("7\n", String::from_utf8(output).unwrap());
+    }
+
+}
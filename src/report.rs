@@ -0,0 +1,131 @@
+//! Machine-readable run results.
+
+use crate::cli::Selection;
+use aoc::answers::{Answers, Verdict};
+use aoc::json::Value;
+use aoc::registry::Day;
+use aoc::solver::{Answer, Run};
+use aoc::timing::Timing;
+
+/// A day together with its run and recorded answers, or why it couldn't run.
+pub type Report<'a> = (&'a Day, Result<(Run, Answers), String>);
+
+fn timing(timing: &Timing) -> Value {
+    let nanos = |x: Option<std::time::Duration>| Value::from(x.map(|x| x.as_nanos() as usize));
+    Value::object(vec![
+        ("min_ns", nanos(timing.min())),
+        ("median_ns", nanos(timing.median())),
+        ("samples", timing.samples.len().into())
+    ])
+}
+
+fn answer(answer: &Answer) -> Value {
+    match answer {
+        Answer::Integer(value) => Value::Integer(*value),
+        Answer::Unsolved => Value::Null,
+        answer => answer.to_string().into()
+    }
+}
+
+/// Builds the `--format json` document: each selected day with its parse
+/// time and, per part, the answer, elapsed time and verification status.
+pub fn json(year: usize, reports: &[Report], selection: &Selection) -> Value {
+    let days = reports.iter()
+        .map(|(day, result)| {
+            let (run, answers) = match result {
+                Ok(result) => result,
+                Err(message) => return Value::object(vec![
+                    ("day", day.number.into()),
+                    ("error", message.as_str().into())
+                ])
+            };
+            let parts = run.parts.iter()
+                .map(|part| {
+                    let mut entries = vec![("part", part.part.into())];
+                    match &part.outcome {
+                        Ok(value) => {
+                            entries.push(("answer", answer(value)));
+                            let (status, expected) = match answers.check(part.part, value) {
+                                Verdict::Correct => ("correct", None),
+                                Verdict::Wrong(expected) => ("wrong", Some(expected)),
+                                Verdict::Unrecorded => ("unrecorded", None)
+                            };
+                            entries.push(("status", status.into()));
+                            if let Some(expected) = expected {
+                                entries.push(("expected", expected.into()));
+                            }
+                        },
+                        Err(message) => entries.push(("error", message.as_str().into()))
+                    }
+                    entries.push(("elapsed", timing(&part.timing)));
+                    Value::object(entries)
+                })
+                .collect();
+            Value::object(vec![
+                ("day", day.number.into()),
+                ("parse", timing(&run.parse)),
+                ("parts", Value::Array(parts))
+            ])
+        })
+        .collect();
+    Value::object(vec![
+        ("year", year.into()),
+        ("repeat", selection.repeat.unwrap_or(1).into()),
+        ("days", Value::Array(days))
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use aoc::solver::PartRun;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_answers_and_status() {
+        let day = Day {
+            year: 2019,
+            number: 4,
+            solve: |_, _, _| unreachable!(),
+            parts: 2
+        };
+        let run = Run {
+            parse: Timing {
+                samples: vec![Duration::from_nanos(30)]
+            },
+            parts: vec![
+                PartRun {
+                    part: 1,
+                    outcome: Ok(Answer::Integer(945)),
+                    timing: Timing {
+                        samples: vec![Duration::from_nanos(10), Duration::from_nanos(20)]
+                    }
+                },
+                PartRun {
+                    part: 2,
+                    outcome: Err("boom".to_string()),
+                    timing: Timing::new()
+                }
+            ]
+        };
+        let answers = Answers {
+            part1: Some("945".to_string()),
+            part2: None
+        };
+        let missing = Day {
+            number: 5,
+            ..day
+        };
+        let reports = vec![(&day, Ok((run, answers))), (&missing, Err("input file missing".to_string()))];
+        let json = json(2019, &reports, &Selection::default()).to_string();
+        assert_eq!(concat!(
+            r#"{"year":2019,"repeat":1,"days":["#,
+            r#"{"day":4,"parse":{"min_ns":30,"median_ns":30,"samples":1},"parts":["#,
+            r#"{"part":1,"answer":945,"status":"correct","elapsed":{"min_ns":10,"median_ns":15,"samples":2}},"#,
+            r#"{"part":2,"error":"boom","elapsed":{"min_ns":null,"median_ns":null,"samples":0}}]},"#,
+            r#"{"day":5,"error":"input file missing"}]}"#
+        ), json);
+    }
+
+}
@@ -0,0 +1,93 @@
+//! Generates the files for a new day. The registry is built from the files
+//! under `src/yYYYY/` by build.rs, so nothing else needs editing.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = "\
+use crate::error::Result;
+use crate::parse;
+use crate::solver::{Answer, Solver};
+
+pub struct Day{N};
+
+impl Solver for Day{N} {
+    type Input = Vec<String>;
+
+    fn parse(input: &str) -> Result<Vec<String>> {
+        Ok(parse::lines(input)?)
+    }
+
+    fn part1(_lines: &Vec<String>) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+
+    fn part2(_lines: &Vec<String>) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn solves_example() {
+        let input = Day{N}::parse(\"\").unwrap();
+        assert_eq!(Ok(Answer::Unsolved), Day{N}::part1(&input));
+    }
+
+}
+";
+
+pub fn module(day: usize) -> String {
+    TEMPLATE.replace("{N}", &day.to_string())
+}
+
+/// Writes `src/yYYYY/dayN.rs` and an empty `inputs/YYYY/N.txt`, returning the
+/// files created. Existing files are never overwritten: the module is an
+/// error, the input is left alone.
+pub fn new_day(src: &Path, inputs: &Path, year: usize, day: usize) -> Result<Vec<PathBuf>, String> {
+    if !(1..=25).contains(&day) {
+        return Err(format!("invalid day {}, expected 1-25", day));
+    }
+    let module_path = src.join(format!("y{}", year)).join(format!("day{}.rs", day));
+    if module_path.exists() {
+        return Err(format!("{} already exists", module_path.display()));
+    }
+    let input_path = inputs.join(year.to_string()).join(format!("{}.txt", day));
+    let mut created = Vec::new();
+    for (path, contents) in &[(&module_path, module(day)), (&input_path, String::new())] {
+        if path.exists() {
+            continue;
+        }
+        let dir = path.parent().unwrap();
+        fs::create_dir_all(dir).map_err(|x| format!("failed to create {}: {}", dir.display(), x))?;
+        fs::write(path, contents).map_err(|x| format!("failed to write {}: {}", path.display(), x))?;
+        created.push(path.to_path_buf());
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+
+    #[test]
+    fn generates_module_and_input_placeholder() {
+        let dir = TempDir::new("scaffold");
+        let (src, inputs) = (dir.join("src"), dir.join("inputs"));
+        let created = new_day(&src, &inputs, 2020, 12).unwrap();
+        assert_eq!(vec![src.join("y2020/day12.rs"), inputs.join("2020/12.txt")], created);
+        let module = fs::read_to_string(src.join("y2020/day12.rs")).unwrap();
+        assert!(module.contains("pub struct Day12;"));
+        assert!(module.contains("impl Solver for Day12 {"));
+        assert!(module.contains("#[cfg(test)]"));
+        assert!(new_day(&src, &inputs, 2020, 12).unwrap_err().contains("already exists"));
+        assert!(new_day(&src, &inputs, 2020, 26).is_err());
+    }
+
+}
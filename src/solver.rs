@@ -0,0 +1,236 @@
+//! The interface every day implements, and the runner-side helper that
+//! parses, solves and times it.
+
+use crate::error::Result;
+use crate::timing::Timing;
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Instant;
+
+/// A puzzle solution. Parsing is separate from the parts so the parsed input
+/// can be shared between them. Every step reports failure as an
+/// [`Error`](crate::error::Error) rather than panicking.
+pub trait Solver {
+    type Input;
+    /// How many parts are implemented; `part2` isn't run while this is 1.
+    const PARTS: usize = 2;
+    fn parse(input: &str) -> Result<Self::Input>;
+    fn part1(input: &Self::Input) -> Result<Answer>;
+    fn part2(input: &Self::Input) -> Result<Answer>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Answer {
+    Integer(i64),
+    Text(String),
+    Image(Image),
+    Unsolved
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Answer {
+        Answer::Integer(value)
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Answer {
+        Answer::Integer(value as i64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<Image> for Answer {
+    fn from(value: Image) -> Answer {
+        Answer::Image(value)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(value) => write!(f, "{}", value),
+            Answer::Image(image) => write!(f, "{}", image),
+            Answer::Unsolved => write!(f, "unsolved")
+        }
+    }
+}
+
+/// Monochrome image, for puzzles whose answer is drawn rather than computed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (index, row) in self.pixels.chunks(self.width).enumerate() {
+            if index > 0 {
+                writeln!(f)?;
+            }
+            for pixel in row {
+                write!(f, "{}", if *pixel { "\u{2588}" } else { " " })?; // full block
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Result of one part; `Err` holds the solver's error, or the panic message
+/// if it panicked.
+pub type Outcome = std::result::Result<Answer, String>;
+
+#[derive(Debug)]
+pub struct PartRun {
+    pub part: usize,
+    pub outcome: Outcome,
+    pub timing: Timing
+}
+
+/// Everything measured while solving one day.
+#[derive(Debug)]
+pub struct Run {
+    pub parse: Timing,
+    pub parts: Vec<PartRun>
+}
+
+/// Parses `input` and runs the requested parts `repeat` times each, containing
+/// any panics. The outcome is taken from the first run; later runs only add
+/// timing samples.
+pub fn solve<S: Solver>(input: &str, parts: &[usize], repeat: usize) -> Run {
+    let mut parse = Timing::new();
+    let parsed = time(&mut parse, || panic::catch_unwind(|| S::parse(input)))
+        .map_err(panic_message)
+        .and_then(|x| x.map_err(|error| format!("invalid input: {}", error)));
+    let parsed = match parsed {
+        Ok(parsed) => parsed,
+        Err(message) => {
+            let parts = parts.iter()
+                .map(|part| PartRun {
+                    part: *part,
+                    outcome: Err(message.clone()),
+                    timing: Timing::new()
+                })
+                .collect();
+            return Run {
+                parse,
+                parts
+            };
+        }
+    };
+    for _ in 1..repeat {
+        time(&mut parse, || S::parse(input)).ok();
+    }
+    let parts = parts.iter()
+        .map(|part| {
+            let run = || panic::catch_unwind(AssertUnwindSafe(|| match part {
+                1 => S::part1(&parsed),
+                _ => S::part2(&parsed)
+            }));
+            let mut timing = Timing::new();
+            let outcome = time(&mut timing, run)
+                .map_err(panic_message)
+                .and_then(|x| x.map_err(|error| error.to_string()));
+            if outcome.is_ok() {
+                for _ in 1..repeat {
+                    time(&mut timing, run).ok();
+                }
+            }
+            PartRun {
+                part: *part,
+                outcome,
+                timing
+            }
+        })
+        .collect();
+    Run {
+        parse,
+        parts
+    }
+}
+
+fn time<T, F: FnOnce() -> T>(timing: &mut Timing, f: F) -> T {
+    let start = Instant::now();
+    let value = f();
+    timing.samples.push(start.elapsed());
+    value
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload.downcast_ref::<&str>().map(|x| x.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "panicked".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::error::Error;
+    use crate::parse;
+
+    struct Sum;
+
+    impl Solver for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(input: &str) -> Result<Vec<i64>> {
+            Ok(parse::comma_list(input)?)
+        }
+
+        fn part1(input: &Vec<i64>) -> Result<Answer> {
+            if input.iter().any(|x| *x < 0) {
+                return Err(Error::msg("negative value").context("summing"));
+            }
+            Ok(input.iter().sum::<i64>().into())
+        }
+
+        fn part2(_: &Vec<i64>) -> Result<Answer> {
+            panic!("not yet")
+        }
+    }
+
+    #[test]
+    fn runs_selected_parts() {
+        let run = solve::<Sum>("1,2,3", &[1, 2], 1);
+        assert_eq!(Ok(Answer::Integer(6)), run.parts[0].outcome);
+        assert_eq!(Err("not yet".to_string()), run.parts[1].outcome);
+        assert_eq!(2, solve::<Sum>("1,2,3", &[2], 1).parts[0].part);
+        assert_eq!(Err("summing: negative value".to_string()), solve::<Sum>("1,-2", &[1], 1).parts[0].outcome);
+    }
+
+    #[test]
+    fn reports_parse_errors_for_every_part() {
+        let run = solve::<Sum>("1,x", &[1, 2], 1);
+        let error = Err("invalid input: line 1, column 3: can't parse `x`: invalid digit found in string".to_string());
+        assert!(run.parts.iter().all(|x| x.outcome == error));
+    }
+
+    #[test]
+    fn repeats_successful_parts() {
+        let run = solve::<Sum>("1,2,3", &[1, 2], 5);
+        assert_eq!(5, run.parse.samples.len());
+        assert_eq!(5, run.parts[0].timing.samples.len());
+        assert_eq!(1, run.parts[1].timing.samples.len());
+    }
+
+    #[test]
+    fn renders_images() {
+        let image = Image {
+            width: 3,
+            height: 2,
+            pixels: vec![true, false, true, false, true, false]
+        };
+        assert_eq!("\u{2588} \u{2588}\n \u{2588} ", Answer::from(image).to_string());
+    }
+
+}
@@ -0,0 +1,98 @@
+//! The `status` table: one row per day of a year, built from the recorded run
+//! history and answers rather than by solving anything.
+
+use aoc::history::{History, Record, Status};
+use aoc::timing::format_duration;
+use std::collections::BTreeMap;
+
+const DAYS: usize = 25;
+
+/// What's known about one year.
+pub struct Progress<'a> {
+    pub year: usize,
+    pub implemented: Vec<usize>,
+    pub history: &'a History,
+    /// Recorded answers per day, i.e. stars earned.
+    pub stars: BTreeMap<usize, usize>
+}
+
+fn ago(at: u64, now: u64) -> String {
+    let seconds = now.saturating_sub(at);
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{}m ago", seconds / 60),
+        3600..=86399 => format!("{}h ago", seconds / 3600),
+        _ => format!("{}d ago", seconds / 86400)
+    }
+}
+
+fn part(record: Option<&Record>) -> String {
+    match record {
+        Some(record) => {
+            let elapsed = record.elapsed.map(format_duration).unwrap_or_default();
+            format!("{:<9} {:>8}", record.status.name(), elapsed)
+        },
+        None => format!("{:<9} {:>8}", "not run", "")
+    }
+}
+
+pub fn table(progress: &Progress, now: u64) -> String {
+    let stars: usize = progress.stars.values().sum();
+    let verified = progress.history.records.values().filter(|x| x.status == Status::Correct).count();
+    let mut lines = vec![
+        format!("advent of code {}: {} of {} stars, {} days implemented, {} parts verified",
+            progress.year, stars, DAYS * 2, progress.implemented.len(), verified),
+        String::new(),
+        format!("{:>3}  {:<18}  {:<18}  {:<5}  {}", "day", "part 1", "part 2", "stars", "last run"),
+    ];
+    for day in 1..=DAYS {
+        let stars = "*".repeat(progress.stars.get(&day).copied().unwrap_or(0));
+        let line = if progress.implemented.contains(&day) {
+            let records = [progress.history.get(day, 1), progress.history.get(day, 2)];
+            let last = records.iter().flatten().map(|x| x.at).max().map(|x| ago(x, now)).unwrap_or_default();
+            format!("{:>3}  {}  {}  {:<5}  {}", day, part(records[0]), part(records[1]), stars, last)
+        }
+        else {
+            format!("{:>3}  {:<38}  {}", day, "not implemented", stars)
+        };
+        lines.push(line.trim_end().to_string());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn renders_progress_table() {
+        let mut history = History::default();
+        history.record(1, 1, Record {
+            status: Status::Correct,
+            elapsed: Some(Duration::from_micros(12)),
+            at: 1000
+        });
+        history.record(1, 2, Record {
+            status: Status::Failed,
+            elapsed: None,
+            at: 4600
+        });
+        let progress = Progress {
+            year: 2019,
+            implemented: vec![1, 2],
+            history: &history,
+            stars: vec![(1, 1), (3, 2)].into_iter().collect()
+        };
+        let table = table(&progress, 4600 + 7200);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!("advent of code 2019: 3 of 50 stars, 2 days implemented, 1 parts verified", lines[0]);
+        assert_eq!("day  part 1              part 2              stars  last run", lines[2]);
+        assert_eq!("  1  correct     12.0µs  failed              *      2h ago", lines[3]);
+        assert_eq!("  2  not run             not run", lines[4]);
+        assert_eq!("  3  not implemented                         **", lines[5]);
+        assert_eq!(28, lines.len());
+    }
+
+}
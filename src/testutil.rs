@@ -0,0 +1,43 @@
+//! Fixtures shared by the tests of the library and the binary.
+
+use std::env;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// An empty directory under the system temp directory, removed when dropped,
+/// so it's cleaned up even if the test fails.
+pub struct TempDir {
+    path: PathBuf
+}
+
+impl TempDir {
+
+    pub fn new(name: &str) -> TempDir {
+        let count = COUNT.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("aoc-{}-{}-{}", name, process::id(), count));
+        fs::create_dir_all(&path).unwrap();
+        TempDir {
+            path
+        }
+    }
+
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.path).ok();
+    }
+}
@@ -0,0 +1,151 @@
+//! Timing samples and the summary table printed by `--time`.
+
+use crate::solver::Run;
+use std::time::Duration;
+
+/// Elapsed times for repeated runs of the same step.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Timing {
+    pub samples: Vec<Duration>
+}
+
+impl Timing {
+
+    pub fn new() -> Timing {
+        Default::default()
+    }
+
+    pub fn min(&self) -> Option<Duration> {
+        self.samples.iter().min().copied()
+    }
+
+    pub fn median(&self) -> Option<Duration> {
+        let mut samples = self.samples.clone();
+        samples.sort();
+        match samples.len() {
+            0 => None,
+            n if n % 2 == 1 => Some(samples[n / 2]),
+            n => Some((samples[n / 2 - 1] + samples[n / 2]) / 2)
+        }
+    }
+
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{}ns", nanos)
+    }
+    else if nanos < 1_000_000 {
+        format!("{:.1}µs", nanos as f64 / 1e3)
+    }
+    else if nanos < 1_000_000_000 {
+        format!("{:.2}ms", nanos as f64 / 1e6)
+    }
+    else {
+        format!("{:.2}s", nanos as f64 / 1e9)
+    }
+}
+
+/// Renders a table of parse/part times per day with a total row. With
+/// `repeated` set each cell shows `min / median`, otherwise the single sample.
+pub fn summary(runs: &[(usize, &Run)], repeated: bool) -> String {
+    let cell = |min: Option<Duration>, median: Option<Duration>| match (min, median) {
+        (Some(min), Some(median)) if repeated => format!("{} / {}", format_duration(min), format_duration(median)),
+        (Some(min), _) => format_duration(min),
+        _ => "-".to_string()
+    };
+    let sum = |durations: &[Option<Duration>]| -> Option<Duration> {
+        durations.iter().flatten().copied().reduce(|a, b| a + b)
+    };
+
+    let mut rows = vec![vec![
+        "day".to_string(),
+        "parse".to_string(),
+        "part 1".to_string(),
+        "part 2".to_string(),
+        "total".to_string()
+    ]];
+    let mut totals = vec![(Vec::new(), Vec::new()); 4];
+    for (day, run) in runs {
+        let part = |n: usize| run.parts.iter().find(|x| x.part == n).map(|x| &x.timing);
+        let timings = [Some(&run.parse), part(1), part(2)];
+        let mins: Vec<Option<Duration>> = timings.iter().map(|x| x.and_then(|x| x.min())).collect();
+        let medians: Vec<Option<Duration>> = timings.iter().map(|x| x.and_then(|x| x.median())).collect();
+        let mut row = vec![day.to_string()];
+        for index in 0..3 {
+            row.push(cell(mins[index], medians[index]));
+            totals[index].0.push(mins[index]);
+            totals[index].1.push(medians[index]);
+        }
+        row.push(cell(sum(&mins), sum(&medians)));
+        totals[3].0.push(sum(&mins));
+        totals[3].1.push(sum(&medians));
+        rows.push(row);
+    }
+    let mut row = vec!["total".to_string()];
+    row.extend(totals.iter().map(|(mins, medians)| cell(sum(mins), sum(medians))));
+    rows.push(row);
+
+    let widths: Vec<usize> = (0..5)
+        .map(|column| rows.iter().map(|row| row[column].chars().count()).max().unwrap())
+        .collect();
+    let lines: Vec<String> = rows.iter()
+        .map(|row| {
+            let cells: Vec<String> = row.iter().zip(&widths)
+                .map(|(cell, width)| format!("{:>1$}", cell, width))
+                .collect();
+            cells.join("  ").trim_end().to_string()
+        })
+        .collect();
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::solver::PartRun;
+
+    fn timing(millis: &[u64]) -> Timing {
+        Timing {
+            samples: millis.iter().map(|x| Duration::from_millis(*x)).collect()
+        }
+    }
+
+    #[test]
+    fn computes_min_and_median() {
+        assert_eq!(None, Timing::new().median());
+        assert_eq!(Some(Duration::from_millis(2)), timing(&[5, 2, 3]).min());
+        assert_eq!(Some(Duration::from_millis(3)), timing(&[5, 2, 3]).median());
+        assert_eq!(Some(Duration::from_millis(4)), timing(&[5, 2, 3, 9]).median());
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!("850ns", format_duration(Duration::from_nanos(850)));
+        assert_eq!("12.5µs", format_duration(Duration::from_nanos(12_500)));
+        assert_eq!("3.25ms", format_duration(Duration::from_micros(3_250)));
+        assert_eq!("1.50s", format_duration(Duration::from_millis(1_500)));
+    }
+
+    #[test]
+    fn renders_summary_with_totals() {
+        let run = Run {
+            parse: timing(&[1]),
+            parts: vec![PartRun {
+                part: 2,
+                outcome: Err("failed".to_string()),
+                timing: timing(&[4])
+            }]
+        };
+        let table = summary(&[(3, &run), (4, &run)], false);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!("  day   parse  part 1  part 2    total", lines[0]);
+        assert_eq!("    3  1.00ms       -  4.00ms   5.00ms", lines[1]);
+        assert_eq!("total  2.00ms       -  8.00ms  10.00ms", lines[3]);
+        let table = summary(&[(3, &run)], true);
+        assert!(table.contains("1.00ms / 1.00ms"));
+    }
+
+}
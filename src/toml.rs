@@ -0,0 +1,226 @@
+//! Just enough TOML for the runner's own files: `[table]` headers and
+//! `key = value` pairs holding integers, booleans, basic strings and `'''`
+//! multi-line literal strings. Arrays, inline tables and dotted keys are not
+//! supported.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Integer(i64),
+    Boolean(bool),
+    String(String)
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::String(value) if value.contains('\n') && !value.contains("'''") => {
+                write!(f, "'''\n{}'''", value)
+            },
+            Value::String(value) => {
+                write!(f, "\"")?;
+                for c in value.chars() {
+                    match c {
+                        '"' => write!(f, "\\\"")?,
+                        '\\' => write!(f, "\\\\")?,
+                        '\n' => write!(f, "\\n")?,
+                        '\t' => write!(f, "\\t")?,
+                        _ => write!(f, "{}", c)?
+                    }
+                }
+                write!(f, "\"")
+            }
+        }
+    }
+}
+
+pub type Table = BTreeMap<String, Value>;
+
+/// A parsed file; keys before the first header live in the root table `""`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Document {
+    pub tables: BTreeMap<String, Table>
+}
+
+impl Document {
+
+    pub fn new() -> Document {
+        Default::default()
+    }
+
+    pub fn get(&self, table: &str, key: &str) -> Option<&Value> {
+        self.tables.get(table).and_then(|x| x.get(key))
+    }
+
+    pub fn set(&mut self, table: &str, key: &str, value: Value) {
+        self.tables.entry(table.to_string()).or_default().insert(key.to_string(), value);
+    }
+
+}
+
+impl fmt::Display for Document {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut first = true;
+        for (name, table) in &self.tables {
+            if !name.is_empty() {
+                if !first {
+                    writeln!(f)?;
+                }
+                writeln!(f, "[{}]", name)?;
+            }
+            for (key, value) in table {
+                writeln!(f, "{} = {}", key, value)?;
+            }
+            first = false;
+        }
+        Ok(())
+    }
+}
+
+/// Parses `text`, reporting errors as `line: message`.
+pub fn parse(text: &str) -> Result<Document, String> {
+    let mut document = Document::new();
+    let mut table = String::new();
+    let mut lines = text.lines().enumerate();
+    while let Some((index, line)) = lines.next() {
+        let error = |message: &str| format!("line {}: {}", index + 1, message);
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('[') {
+            let name = line.strip_suffix(']').ok_or_else(|| error("expected `]`"))?[1..].trim();
+            if !is_key(name) {
+                return Err(error(&format!("invalid table name `{}`", name)));
+            }
+            table = name.to_string();
+            document.tables.entry(table.clone()).or_default();
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        let key = parts.next().unwrap().trim();
+        let raw = parts.next().ok_or_else(|| error("expected `key = value`"))?.trim();
+        if !is_key(key) {
+            return Err(error(&format!("invalid key `{}`", key)));
+        }
+        if document.get(&table, key).is_some() {
+            return Err(error(&format!("duplicate key `{}`", key)));
+        }
+        let value = if let Some(rest) = raw.strip_prefix("'''") {
+            let mut value = String::new();
+            let mut rest = rest.to_string();
+            let mut first = true;
+            loop {
+                if let Some(end) = rest.find("'''") {
+                    value.push_str(&rest[..end]);
+                    if !rest[end + 3..].trim().is_empty() && !rest[end + 3..].trim().starts_with('#') {
+                        return Err(error("unexpected text after string"));
+                    }
+                    break;
+                }
+                // A newline straight after the opening delimiter is trimmed.
+                if !(first && rest.is_empty()) {
+                    value.push_str(&rest);
+                    value.push('\n');
+                }
+                first = false;
+                rest = lines.next().ok_or_else(|| error("unterminated string"))?.1.to_string();
+            }
+            Value::String(value)
+        }
+        else {
+            scalar(raw).map_err(|x| error(&x))?
+        };
+        document.set(&table, key, value);
+    }
+    Ok(document)
+}
+
+fn is_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn scalar(raw: &str) -> Result<Value, String> {
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        loop {
+            match chars.next() {
+                Some('"') => break,
+                Some('\\') => value.push(match chars.next() {
+                    Some('n') => '\n',
+                    Some('t') => '\t',
+                    Some('"') => '"',
+                    Some('\\') => '\\',
+                    _ => return Err("invalid escape sequence".to_string())
+                }),
+                Some(c) => value.push(c),
+                None => return Err("unterminated string".to_string())
+            }
+        }
+        let rest = chars.as_str().trim();
+        if !rest.is_empty() && !rest.starts_with('#') {
+            return Err("unexpected text after string".to_string());
+        }
+        return Ok(Value::String(value));
+    }
+    let raw = raw.split('#').next().unwrap().trim();
+    match raw {
+        "true" => Ok(Value::Boolean(true)),
+        "false" => Ok(Value::Boolean(false)),
+        _ => raw.replace('_', "").parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| format!("invalid value `{}`", raw))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn parses_tables_and_values() {
+        let text = "\
+# recorded answers
+part1 = 3_224_048
+done = true
+
+[submit]
+url = \"http://localhost:8080/\\\"x\\\"\" # trailing comment
+image = '''
+# .
+ ##
+'''
+";
+        let document = parse(text).unwrap();
+        assert_eq!(Some(&Value::Integer(3224048)), document.get("", "part1"));
+        assert_eq!(Some(&Value::Boolean(true)), document.get("", "done"));
+        assert_eq!(Some(&Value::String("http://localhost:8080/\"x\"".to_string())), document.get("submit", "url"));
+        assert_eq!(Some(&Value::String("# .\n ##\n".to_string())), document.get("submit", "image"));
+    }
+
+    #[test]
+    fn round_trips_documents() {
+        let mut document = Document::new();
+        document.set("", "part1", Value::Integer(-7));
+        document.set("", "part2", Value::String("\u{2588} \n \u{2588}\n".to_string()));
+        document.set("run", "format", Value::String("json\t\"quoted\"".to_string()));
+        assert_eq!(Ok(document.clone()), parse(&document.to_string()));
+    }
+
+    #[test]
+    fn reports_errors_with_line_numbers() {
+        assert_eq!(Err("line 2: expected `key = value`".to_string()), parse("a = 1\nb"));
+        assert!(parse("a = 1\na = 2").unwrap_err().contains("duplicate key"));
+        assert!(parse("a = nope").unwrap_err().contains("invalid value"));
+        assert!(parse("a = \"open").unwrap_err().contains("unterminated"));
+        assert!(parse("a = '''\nopen").unwrap_err().contains("unterminated"));
+        assert!(parse("[bad name]").is_err());
+    }
+
+}
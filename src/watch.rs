@@ -0,0 +1,179 @@
+//! Re-runs a day whenever its module or input changes. Changes are found by
+//! polling modification times, and each run goes through cargo so that edits
+//! to the module are rebuilt first.
+
+use crate::cli::{Format, Selection};
+use aoc::examples;
+use aoc::input::Source;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const INTERVAL: Duration = Duration::from_millis(500);
+const CLEAR_SCREEN: &str = "\x1b[2J\x1b[H";
+
+/// Tracks the modification times of a set of files; a missing file counts as
+/// a state of its own, so creating or deleting one is a change too.
+pub struct Watcher {
+    paths: Vec<PathBuf>,
+    stamps: Vec<Option<SystemTime>>
+}
+
+impl Watcher {
+
+    pub fn new(paths: Vec<PathBuf>) -> Watcher {
+        let stamps = paths.iter().map(|x| modified(x)).collect();
+        Watcher {
+            paths,
+            stamps
+        }
+    }
+
+    /// Returns true if any file changed since the last call.
+    pub fn changed(&mut self) -> bool {
+        let stamps: Vec<Option<SystemTime>> = self.paths.iter().map(|x| modified(x)).collect();
+        let changed = stamps != self.stamps;
+        self.stamps = stamps;
+        changed
+    }
+
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|x| x.modified()).ok()
+}
+
+/// Arguments for `aoc run` that reproduce the selection for one day. Time and
+/// format are always given, since the run would otherwise take them from the
+/// config rather than from any flags passed to watch.
+fn run_args(selection: &Selection, year: usize, day: usize) -> Vec<String> {
+    let mut args = vec!["run".to_string(), day.to_string(), "--year".to_string(), year.to_string()];
+    if let Some(part) = selection.part {
+        args.extend(vec!["--part".to_string(), part.to_string()]);
+    }
+    args.push(if selection.time { "--time" } else { "--no-time" }.to_string());
+    let format = match selection.format {
+        Format::Text => "text",
+        Format::Json => "json"
+    };
+    args.extend(vec!["--format".to_string(), format.to_string()]);
+    if let Some(repeat) = selection.repeat {
+        args.extend(vec!["--repeat".to_string(), repeat.to_string()]);
+    }
+    if let Some(jobs) = selection.jobs {
+        args.extend(vec!["--jobs".to_string(), jobs.to_string()]);
+    }
+    match &selection.input {
+        Source::Directory(dir) => args.extend(vec!["--inputs".to_string(), dir.display().to_string()]),
+        Source::File(path) => args.extend(vec!["--input".to_string(), path.display().to_string()]),
+        Source::Example { dir, name } => args.extend(vec![
+            "--inputs".to_string(),
+            dir.display().to_string(),
+            "--example".to_string(),
+            name.to_string()
+        ]),
+        Source::Stdin => {}
+    }
+    args
+}
+
+fn cargo(manifest: &Path, args: &[String]) {
+    let status = Command::new("cargo")
+        .arg(&args[0])
+        .arg("--quiet")
+        .arg("--manifest-path")
+        .arg(manifest)
+        .args(&args[1..])
+        .status();
+    if let Err(x) = status {
+        eprintln!("error: failed to run cargo: {}", x);
+    }
+}
+
+/// Watches one day until interrupted.
+pub fn watch(selection: &Selection, year: usize, day: usize) -> Result<(), String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let module = root.join("src").join(format!("y{}", year)).join(format!("day{}.rs", day));
+    let input = match &selection.input {
+        Source::Directory(dir) => dir.join(year.to_string()).join(format!("{}.txt", day)),
+        Source::File(path) => path.clone(),
+        Source::Example { dir, name } => examples::path(dir, year, day, name, "txt"),
+        Source::Stdin => return Err("watch can't read input from stdin".to_string())
+    };
+    if !module.exists() {
+        return Err(format!("{} not found", module.display()));
+    }
+
+    let manifest = root.join("Cargo.toml");
+    let mut run = vec!["run".to_string(), "--bin".to_string(), "aoc".to_string(), "--".to_string()];
+    run.extend(run_args(selection, year, day));
+    let test = vec!["test".to_string(), format!("y{}::day{}::", year, day)];
+
+    // An input fetched into the cache stands in for a missing one.
+    let cached = selection.input.cache_path(year, day);
+    let mut paths = vec![module.clone(), input.clone()];
+    paths.extend(cached.clone());
+    let mut watcher = Watcher::new(paths);
+    loop {
+        print!("{}", CLEAR_SCREEN);
+        match &cached {
+            Some(cached) => println!("watching {}, {} and {}", module.display(), input.display(), cached.display()),
+            None => println!("watching {} and {}", module.display(), input.display())
+        }
+        println!();
+        cargo(&manifest, &run);
+        println!();
+        cargo(&manifest, &test);
+        while !watcher.changed() {
+            thread::sleep(INTERVAL);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+    use crate::testutil::TempDir;
+    use std::fs::File;
+
+    #[test]
+    fn detects_modified_created_and_deleted_files() {
+        let dir = TempDir::new("watch");
+        let (module, input) = (dir.join("day1.rs"), dir.join("1.txt"));
+        fs::write(&module, "").unwrap();
+        let mut watcher = Watcher::new(vec![module.clone(), input.clone()]);
+        assert!(!watcher.changed());
+        File::options().write(true).open(&module).unwrap()
+            .set_modified(SystemTime::now() + Duration::from_secs(5)).unwrap();
+        assert!(watcher.changed());
+        assert!(!watcher.changed());
+        fs::write(&input, "12").unwrap();
+        assert!(watcher.changed());
+        fs::remove_file(&input).unwrap();
+        assert!(watcher.changed());
+    }
+
+    #[test]
+    fn forwards_the_selection_to_run() {
+        let selection = Selection {
+            part: Some(2),
+            input: Source::File(PathBuf::from("mine.txt")),
+            ..Default::default()
+        };
+        let args = run_args(&selection, 2019, 7);
+        assert_eq!(vec!["run", "7", "--year", "2019", "--part", "2", "--no-time", "--format", "text", "--input", "mine.txt"], args);
+        let selection = Selection {
+            time: true,
+            repeat: Some(5),
+            jobs: Some(2),
+            format: Format::Json,
+            ..Default::default()
+        };
+        let args = run_args(&selection, 2019, 7);
+        assert_eq!(vec!["run", "7", "--year", "2019", "--time", "--format", "json", "--repeat", "5", "--jobs", "2", "--inputs", "inputs"], args);
+    }
+
+}
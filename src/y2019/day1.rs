@@ -0,0 +1,63 @@
+
+use crate::error::Result;
+use crate::parse;
+use crate::solver::{Answer, Solver};
+
+pub struct Day1;
+
+impl Solver for Day1 {
+    type Input = Vec<f32>;
+
+    fn parse(input: &str) -> Result<Vec<f32>> {
+        Ok(parse::lines(input)?)
+    }
+
+    fn part1(masses: &Vec<f32>) -> Result<Answer> {
+        let fuel_requirement_sum: f32 = masses.iter().copied().map(fuel_required).sum();
+        Ok((fuel_requirement_sum as i64).into())
+    }
+
+    fn part2(masses: &Vec<f32>) -> Result<Answer> {
+        let fuel_requirement_total_sum: f32 = masses.iter().copied().map(total_fuel_required).sum();
+        Ok((fuel_requirement_total_sum as i64).into())
+    }
+}
+
+fn fuel_required(mass: f32) -> f32 {
+    (mass / 3.0).floor() - 2.0
+}
+
+fn total_fuel_required(mass: f32) -> f32 {
+    let fuel = fuel_required(mass);
+    if fuel <= 0.0 { return 0.0 };
+    fuel + total_fuel_required(fuel)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn computes_expected_total_fuel_required() {
+        assert_eq!(2.0, total_fuel_required(14.0));
+        assert_eq!(966.0, total_fuel_required(1969.0));
+        assert_eq!(50346.0, total_fuel_required(100756.0));
+    }
+
+    #[test]
+    fn sums_fuel_for_all_modules() {
+        let masses = Day1::parse("12\n14\n1969\n100756").unwrap();
+        assert_eq!(Ok(Answer::Integer(2 + 2 + 654 + 33583)), Day1::part1(&masses));
+        assert_eq!(Ok(Answer::Integer(2 + 2 + 966 + 50346)), Day1::part2(&masses));
+    }
+
+    #[test]
+    fn computed_expected_fuel_required() {
+        assert_eq!(2.0, fuel_required(12.0));
+        assert_eq!(2.0, fuel_required(14.0));
+        assert_eq!(654.0, fuel_required(1969.0));
+        assert_eq!(33583.0, fuel_required(100756.0));
+    }
+
+}
\ No newline at end of file
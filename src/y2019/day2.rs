@@ -0,0 +1,68 @@
+use crate::intcode::Intcode;
+use crate::error::{Error, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
+
+pub struct Day2;
+
+impl Solver for Day2 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        Ok(parse::comma_list(input)?)
+    }
+
+    fn part1(program: &Vec<i64>) -> Result<Answer> {
+        let mut vm = Intcode::new();
+
+        vm.reset(program.clone());
+        vm.write(1, 12);
+        vm.write(2, 2);
+        vm.run();
+
+        Ok(vm.read(0).into())
+    }
+
+    fn part2(program: &Vec<i64>) -> Result<Answer> {
+        let mut vm = Intcode::new();
+
+        for noun in 0..100 {
+            for verb in 0..100 {
+                vm.reset(program.clone());
+                vm.write(1, noun);
+                vm.write(2, verb);
+                vm.run();
+
+                if vm.read(0) == 19690720 {
+                    let answer = 100 * noun + verb;
+                    return Ok(answer.into());
+                }
+            }
+        }
+
+        Err(Error::msg("no noun and verb produce 19690720"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    fn assert_state(program: &str, expected_state: Vec<i64>) {
+        let mut vm = Intcode::new();
+        let program = Intcode::compile(program);
+        vm.reset(program);
+        vm.run();
+        let state = &vm.memory[0..expected_state.len()];
+        assert_eq!(&expected_state[0..], state);
+    }
+
+    #[test]
+    fn computer_produces_expected_states() {
+        assert_state("1,0,0,0,99", vec![2,0,0,0,99]);
+        assert_state("2,3,0,3,99", vec![2,3,0,6,99]);
+        assert_state("2,4,4,5,99,0", vec![2,4,4,5,99,9801]);
+    }
+
+}
\ No newline at end of file
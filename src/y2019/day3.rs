@@ -1,21 +1,32 @@
+use crate::error::{Context, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
 use std::collections::{HashMap, HashSet};
 
-pub fn solve() {
-    let input = include_str!("./inputs/3.txt");
-    let grid = trace(input);
-    let min_distance = min_distance(&grid);
-    let min_steps = min_steps(&grid);
+pub struct Day3;
 
-    println!("{}", min_distance);
-    println!("{}", min_steps);
+impl Solver for Day3 {
+    type Input = Grid;
+
+    fn parse(input: &str) -> Result<Grid> {
+        Ok(trace(input)?)
+    }
+
+    fn part1(grid: &Grid) -> Result<Answer> {
+        Ok((min_distance(grid).context("the wires never cross")? as i64).into())
+    }
+
+    fn part2(grid: &Grid) -> Result<Answer> {
+        Ok((min_steps(grid).context("the wires never cross")? as i64).into())
+    }
 }
 
 type Wire = Vec<Trace>;
 type Trace = (char, usize);
 type Point = (isize, isize);
-type Grid = HashMap<Point, Cell>;
+pub type Grid = HashMap<Point, Cell>;
 
-struct Cell {
+pub struct Cell {
     pub count: isize,
     pub steps: isize
 }
@@ -24,37 +35,35 @@ fn manhatten_distance(a: Point, b: Point) -> isize {
     (a.0 - b.0).abs() + (a.1 - b.1).abs()
 }
 
-fn min_steps(grid: &Grid) -> isize {
-    grid.iter()
-        .map(|(_, cell)| cell.steps)
+fn min_steps(grid: &Grid) -> Option<isize> {
+    grid.values()
+        .map(|cell| cell.steps)
         .min()
-        .unwrap()
 }
 
-fn min_distance(grid: &Grid) -> isize {
-    grid.iter()
-        .map(|(point, _)| manhatten_distance((0, 0), *point))
+fn min_distance(grid: &Grid) -> Option<isize> {
+    grid.keys()
+        .map(|point| manhatten_distance((0, 0), *point))
         .min()
-        .unwrap()
 }
 
-fn trace(input: &str) -> Grid {
-    let mut grid = Grid::new();
-
-    let wires: Vec<Wire> = input
-        .lines()
+fn wires(input: &str) -> parse::Result<Vec<Wire>> {
+    parse::fields(input)
         .map(|line| {
-            line.split(",")
-                .map(|trace| {
-                    let direction = trace.chars().nth(0).unwrap();
-                    let steps = trace[1..trace.len()].parse::<usize>().unwrap();
-                    (direction, steps)
+            line.split(",").iter()
+                .map(|trace| match trace.text.chars().next() {
+                    Some(direction) if "UDLR".contains(direction) => Ok((direction, trace.skip(1).parse()?)),
+                    _ => Err(trace.error("expected a direction of U, D, L or R"))
                 })
                 .collect()
         })
-        .collect();
-    
-    for wire in wires {
+        .collect()
+}
+
+fn trace(input: &str) -> parse::Result<Grid> {
+    let mut grid = Grid::new();
+
+    for wire in wires(input)? {
         let mut distance = 0;
         let mut x = 0;
         let mut y = 0;
@@ -91,7 +100,7 @@ fn trace(input: &str) -> Grid {
 
     grid.retain(|_, cell| cell.count > 1);
 
-    return grid;
+    Ok(grid)
 }
 
 #[cfg(test)]
@@ -104,8 +113,8 @@ mod tests {
         let input = "\
 R75,D30,R83,U83,L12,D49,R71,U7,L72
 U62,R66,U55,R34,D71,R55,D58,R83";
-        let grid = trace(input);
-        assert_eq!(159, min_distance(&grid));
+        let grid = trace(input).unwrap();
+        assert_eq!(Some(159), min_distance(&grid));
     }
 
     #[test]
@@ -113,8 +122,8 @@ U62,R66,U55,R34,D71,R55,D58,R83";
         let input = "\
 R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
 U98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
-        let grid = trace(input);
-        assert_eq!(135, min_distance(&grid));
+        let grid = trace(input).unwrap();
+        assert_eq!(Some(135), min_distance(&grid));
     }
 
     #[test]
@@ -122,8 +131,8 @@ U98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
         let input = "\
 R75,D30,R83,U83,L12,D49,R71,U7,L72
 U62,R66,U55,R34,D71,R55,D58,R83";
-        let grid = trace(input);
-        assert_eq!(610, min_steps(&grid));
+        let grid = trace(input).unwrap();
+        assert_eq!(Some(610), min_steps(&grid));
     }
 
     #[test]
@@ -131,8 +140,8 @@ U62,R66,U55,R34,D71,R55,D58,R83";
         let input = "\
 R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51
 U98,R91,D20,R16,D67,R40,U7,R15,U6,R7";
-        let grid = trace(input);
-        assert_eq!(410, min_steps(&grid));
+        let grid = trace(input).unwrap();
+        assert_eq!(Some(410), min_steps(&grid));
     }
 
 }
\ No newline at end of file
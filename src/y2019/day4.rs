@@ -1,13 +1,29 @@
+use crate::error::{Error, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
 use std::cmp::Ordering;
 use std::ops::Range;
 
-pub fn solve() {
-    let range = 264360..746325+1;
-    let password_count_1 = count_valid_passwords(range.clone(), is_valid_password_1);
-    let password_count_2 = count_valid_passwords(range, is_valid_password_2);
+pub struct Day4;
 
-    println!("{}", password_count_1);
-    println!("{}", password_count_2);
+impl Solver for Day4 {
+    type Input = Range<usize>;
+
+    fn parse(input: &str) -> Result<Range<usize>> {
+        let ranges = parse::records(input, "-", 2, |x| Ok(x[0].parse()?..x[1].parse::<usize>()? + 1))?;
+        match ranges.as_slice() {
+            [range] => Ok(range.clone()),
+            _ => Err(Error::msg("expected a single range"))
+        }
+    }
+
+    fn part1(range: &Range<usize>) -> Result<Answer> {
+        Ok(count_valid_passwords(range.clone(), is_valid_password_1).into())
+    }
+
+    fn part2(range: &Range<usize>) -> Result<Answer> {
+        Ok(count_valid_passwords(range.clone(), is_valid_password_2).into())
+    }
 }
 
 fn count_valid_passwords<F>(range: Range<usize>, predicate: F) -> usize
@@ -18,11 +34,11 @@ fn count_valid_passwords<F>(range: Range<usize>, predicate: F) -> usize
 }
 
 fn is_valid_password_1(input: usize) -> bool {
-    return is_valid_password(input, false);
+    is_valid_password(input, false)
 }
 
 fn is_valid_password_2(input: usize) -> bool {
-    return is_valid_password(input, true);
+    is_valid_password(input, true)
 }
 
 fn is_valid_password(input: usize, minimal_double: bool) -> bool {
@@ -57,16 +73,16 @@ mod tests {
 
     #[test]
     fn passwords_meet_criteria() {
-        assert_eq!(true, is_valid_password_1(111111));
-        assert_eq!(false, is_valid_password_1(223450));
-        assert_eq!(false, is_valid_password_1(123789));
+        assert!(is_valid_password_1(111111));
+        assert!(!is_valid_password_1(223450));
+        assert!(!is_valid_password_1(123789));
     }
 
     #[test]
     fn passwords_match_if_digits_not_part_of_larger_group() {
-        assert_eq!(true, is_valid_password_2(112233));
-        assert_eq!(false, is_valid_password_2(123444));
-        assert_eq!(true, is_valid_password_2(111122));
+        assert!(is_valid_password_2(112233));
+        assert!(!is_valid_password_2(123444));
+        assert!(is_valid_password_2(111122));
     }
 
 }
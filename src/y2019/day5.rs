@@ -1,23 +1,33 @@
-use crate::intcode::{IntcodeBuilder};
-
-pub fn solve() {
-    let input = include_str!("./inputs/5.txt");
-    let execute = |value| {
-        let mut vm = IntcodeBuilder::new()  
-            .with_program(input)
-            .with_inputs(&[value])
-            .build();
-        vm.run();
-        *vm.outputs.last().unwrap()
-    };
+use crate::intcode::IntcodeBuilder;
+use crate::error::{Context, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
+
+pub struct Day5;
+
+impl Solver for Day5 {
+    type Input = Vec<i64>;
 
-    let diagnostic_code = execute(1);
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        Ok(parse::comma_list(input)?)
+    }
 
-    println!("{}", diagnostic_code);
+    fn part1(program: &Vec<i64>) -> Result<Answer> {
+        Ok(execute(program, 1)?.into())
+    }
 
-    let diagnostic_code = execute(5);
+    fn part2(program: &Vec<i64>) -> Result<Answer> {
+        Ok(execute(program, 5)?.into())
+    }
+}
 
-    println!("{}", diagnostic_code);
+fn execute(program: &[i64], value: i64) -> Result<i64> {
+    let mut vm = IntcodeBuilder::new()
+        .with_memory(program)
+        .with_inputs(&[value])
+        .build();
+    vm.run();
+    vm.outputs.last().copied().with_context(|| format!("the diagnostic program produced no output for system {}", value))
 }
 
 #[cfg(test)]
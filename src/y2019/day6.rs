@@ -1,12 +1,26 @@
-use std::collections::{HashMap, HashSet};
-
-pub fn solve() {
-    let input = include_str!("./inputs/6.txt");
-    let orbits = orbits(input);
-    let body = orbits.bodies.iter().position(|x| x.name == "COM").unwrap();
-    let total_orbits = orbits.total_orbits(body, 0);
-    
-    println!("{}", total_orbits);
+use crate::error::{Context, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
+use std::collections::HashMap;
+
+pub struct Day6;
+
+impl Solver for Day6 {
+    type Input = OrbitMap;
+    const PARTS: usize = 1;
+
+    fn parse(input: &str) -> Result<OrbitMap> {
+        Ok(orbits(input)?)
+    }
+
+    fn part1(orbits: &OrbitMap) -> Result<Answer> {
+        let body = orbits.bodies.iter().position(|x| x.name == "COM").context("nothing orbits COM")?;
+        Ok(orbits.total_orbits(body, 0).into())
+    }
+
+    fn part2(_: &OrbitMap) -> Result<Answer> {
+        Ok(Answer::Unsolved)
+    }
 }
 
 #[derive(Debug)]
@@ -21,7 +35,7 @@ struct Body {
 }
 
 #[derive(Debug)]
-struct OrbitMap {
+pub struct OrbitMap {
     bodies: Vec<Body>,
     orbits: Vec<Orbit>
 }
@@ -31,18 +45,6 @@ struct OrbitIterator<'a> {
     index: usize
 }
 
-struct PathIterator<'a> {
-    map: &'a OrbitMap,
-    body: usize
-}
-
-impl<'a> Iterator for PathIterator<'a> {
-    type Item = usize;
-    fn next(&mut self) -> Option<Self::Item> {
-        None
-    }
-}
-
 impl<'a> Clone for OrbitIterator<'a> {
     fn clone(&self) -> Self {
         OrbitIterator {
@@ -66,7 +68,7 @@ impl<'a> Iterator for OrbitIterator<'a> {
 
 impl OrbitMap {
 
-    fn satellites<'a>(&self, body: usize) -> OrbitIterator {
+    fn satellites(&self, body: usize) -> OrbitIterator<'_> {
         let orbits = self.orbits.iter().filter(|x| x.body == body).collect();
         OrbitIterator {
             index: 0,
@@ -74,22 +76,6 @@ impl OrbitMap {
         }
     }
 
-    fn path_to_root<'a>(&self, body: usize) -> PathIterator {
-        PathIterator {
-            map: self,
-            body
-        }
-    }
-
-    fn shortest_path(&self, a: usize, b: usize) -> usize {
-        let path1: HashSet<usize> = self.path_to_root(a).collect();
-        let path2: HashSet<usize> = self.path_to_root(b).collect();
-        let intersection = path1.intersection(&path2).next().unwrap();
-        let index_1 = path1.iter().position(|x| x == intersection).unwrap();
-        let index_2 = path2.iter().position(|x| x == intersection).unwrap();
-        index_1 + index_2
-    }
-
     fn total_orbits(&self, body: usize, depth: usize) -> usize {
         let satellites = self.satellites(body);
         let len = satellites.clone().count();
@@ -104,7 +90,7 @@ impl OrbitMap {
 
 }
 
-fn orbits(input: &str) -> OrbitMap {
+fn orbits(input: &str) -> parse::Result<OrbitMap> {
     let mut bodies = Vec::new();
     let mut indices: HashMap<String, usize> = HashMap::new();
     let insert = |indices: &mut HashMap<String, usize>, bodies: &mut Vec<Body>, name: &String| {
@@ -120,11 +106,9 @@ fn orbits(input: &str) -> OrbitMap {
             }
         }
     };
-    let orbits: Vec<Orbit> = input.lines()
-        .map(|line| {
-            let mut parts = line.split(")");
-            let body = parts.next().unwrap().to_string();
-            let satellite = parts.next().unwrap().to_string();
+    let pairs = parse::records(input, ")", 2, |x| Ok((x[0].text.to_string(), x[1].text.to_string())))?;
+    let orbits: Vec<Orbit> = pairs.into_iter()
+        .map(|(body, satellite)| {
             insert(&mut indices, &mut bodies, &body);
             insert(&mut indices, &mut bodies, &satellite);
             let body = *indices.get(&body).unwrap();
@@ -135,10 +119,10 @@ fn orbits(input: &str) -> OrbitMap {
             }
         })
         .collect();
-    OrbitMap {
+    Ok(OrbitMap {
         bodies,
         orbits
-    }
+    })
 }
 
 #[cfg(test)]
@@ -160,7 +144,7 @@ D)I
 E)J
 J)K
 K)L";
-        let orbits = orbits(input);
+        let orbits = orbits(input).unwrap();
         let total_orbits = orbits.total_orbits(0, 0);
         assert_eq!(42, total_orbits);
     }
@@ -1,28 +1,31 @@
 use crate::intcode::{Intcode, IntcodeBuilder};
+use crate::error::Result;
+use crate::parse;
+use crate::solver::{Answer, Solver};
 
-pub fn solve() {
-    let input = include_str!("./inputs/7.txt");
+pub struct Day7;
 
-    {
-        let phases = vec![0, 1, 2, 3, 4];
-        let max_thruster_signal = permutations(phases)
-            .map(|sequence| amplify_thruster_signal(input, &sequence, false))
-            .max()
-            .unwrap();
+impl Solver for Day7 {
+    type Input = Vec<i64>;
 
-        println!("{}", max_thruster_signal);
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        Ok(parse::comma_list(input)?)
     }
 
-    {
-        let phases = vec![5, 6, 7, 8, 9];
-        let max_thruster_signal = permutations(phases)
-            .map(|sequence| amplify_thruster_signal(input, &sequence, true))
-            .max()
-            .unwrap();
+    fn part1(program: &Vec<i64>) -> Result<Answer> {
+        Ok(max_thruster_signal(program, vec![0, 1, 2, 3, 4], false).into())
+    }
 
-        println!("{}", max_thruster_signal);
+    fn part2(program: &Vec<i64>) -> Result<Answer> {
+        Ok(max_thruster_signal(program, vec![5, 6, 7, 8, 9], true).into())
     }
+}
 
+fn max_thruster_signal(rom: &[i64], phases: Vec<i64>, feedback: bool) -> i64 {
+    permutations(phases)
+        .map(|sequence| amplify_thruster_signal(rom, &sequence, feedback))
+        .max()
+        .unwrap()
 }
 
 struct Permutations {
@@ -65,9 +68,9 @@ struct Amplifier {
 
 impl Amplifier {
 
-    pub fn new(rom: &str, phase: i64) -> Amplifier {
+    pub fn new(rom: &[i64], phase: i64) -> Amplifier {
         let computer = IntcodeBuilder::new()
-            .with_program(rom)
+            .with_memory(rom)
             .with_inputs(&[phase])
             .build();
         Amplifier {
@@ -83,7 +86,7 @@ impl Amplifier {
 
 }
 
-fn amplify_thruster_signal(rom: &str, sequence: &[i64], feedback: bool) -> i64 {
+fn amplify_thruster_signal(rom: &[i64], sequence: &[i64], feedback: bool) -> i64 {
     let mut amps: Vec<Amplifier> = sequence.iter()
         .map(|phase| Amplifier::new(rom, *phase))
         .collect(); 
@@ -98,7 +101,7 @@ fn amplify_thruster_signal(rom: &str, sequence: &[i64], feedback: bool) -> i64 {
             break;
         }
     }
-    return signal;
+    signal
 }
 
 #[cfg(test)]
@@ -110,35 +113,35 @@ mod tests {
     fn produces_max_thruster_signal_43210() {
         let program = "3,15,3,16,1002,16,10,16,1,16,15,15,4,15,99,0,0";
         let phases = vec![4,3,2,1,0];
-        assert_eq!(43210, amplify_thruster_signal(program, &phases, false));
+        assert_eq!(43210, amplify_thruster_signal(&Intcode::compile(program), &phases, false));
     }
 
     #[test]
     fn produces_max_thruster_signal_54321() {
         let program = "3,23,3,24,1002,24,10,24,1002,23,-1,23,101,5,23,23,1,24,23,23,4,23,99,0,0";
         let phases = vec![0,1,2,3,4];
-        assert_eq!(54321, amplify_thruster_signal(program, &phases, false));
+        assert_eq!(54321, amplify_thruster_signal(&Intcode::compile(program), &phases, false));
     }
 
     #[test]
     fn produces_max_thruster_signal_65210() {
         let program = "3,31,3,32,1002,32,10,32,1001,31,-2,31,1007,31,0,33,1002,33,7,33,1,33,31,31,1,32,31,31,4,31,99,0,0,0";
         let phases = vec![1,0,4,3,2];
-        assert_eq!(65210, amplify_thruster_signal(program, &phases, false));
+        assert_eq!(65210, amplify_thruster_signal(&Intcode::compile(program), &phases, false));
     }
 
     #[test]
     fn produces_max_thruster_signal_139629729() {
         let program = "3,26,1001,26,-4,26,3,27,1002,27,2,27,1,27,26,27,4,27,1001,28,-1,28,1005,28,6,99,0,0,5";
         let phases = vec![9,8,7,6,5];
-        assert_eq!(139629729, amplify_thruster_signal(program, &phases, true));
+        assert_eq!(139629729, amplify_thruster_signal(&Intcode::compile(program), &phases, true));
     }
 
     #[test]
     fn produces_max_thruster_signal_18216() {
         let program = "3,52,1001,52,-5,52,3,53,1,52,56,54,1007,54,5,55,1005,55,26,1001,54,-5,54,1105,1,12,1,53,54,53,1008,54,0,55,1001,55,1,55,2,53,55,53,4,53,1001,56,-1,56,1005,56,6,99,0,0,0,0,10";
         let phases = vec![9,7,8,5,6];
-        assert_eq!(18216, amplify_thruster_signal(program, &phases, true));
+        assert_eq!(18216, amplify_thruster_signal(&Intcode::compile(program), &phases, true));
     }
 
 }
\ No newline at end of file
@@ -0,0 +1,101 @@
+use crate::error::{Context, Error, Result};
+use crate::parse;
+use crate::solver::{Answer, Image, Solver};
+
+const WIDTH: usize = 25;
+const HEIGHT: usize = 6;
+
+pub struct Day8;
+
+impl Solver for Day8 {
+    type Input = Vec<u8>;
+
+    fn parse(input: &str) -> Result<Vec<u8>> {
+        Ok(parse::digits(input)?.cells)
+    }
+
+    fn part1(data: &Vec<u8>) -> Result<Answer> {
+        check_size(data, WIDTH, HEIGHT)?;
+        let layer = layer_with_fewest_zeros(data, WIDTH, HEIGHT).context("the image has no layers")?;
+        let image = pixels(data, WIDTH, HEIGHT, layer);
+        let ones = count_pixels(image, |x| { x == 1 });
+        let twos = count_pixels(image, |x| { x == 2 });
+        Ok((ones * twos).into())
+    }
+
+    fn part2(data: &Vec<u8>) -> Result<Answer> {
+        check_size(data, WIDTH, HEIGHT)?;
+        Ok(decode(data, WIDTH, HEIGHT).into())
+    }
+}
+
+fn check_size(data: &[u8], width: usize, height: usize) -> Result<()> {
+    if data.is_empty() || data.len() % (width * height) != 0 {
+        return Err(Error::msg(format!("{} pixels don't make whole {}x{} layers", data.len(), width, height)));
+    }
+    Ok(())
+}
+
+fn decode(data: &[u8], width: usize, height: usize) -> Image {
+    let size = width * height;
+    let layers = data.len() / size;
+
+    let mut flattened_image = vec![2; size];
+
+    for y in 0..height {
+        for x in 0..width {
+            let index = y * width + x;
+            for layer in 0..layers {
+                let index_src = layer * size + index;
+                if flattened_image[index] == 2 {
+                    flattened_image[index] = data[index_src];
+                }
+            }
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels: flattened_image.iter().map(|x| *x == 1).collect()
+    }
+}
+
+fn pixels(image: &[u8], width: usize, height: usize, layer: usize) -> &[u8] {
+    let start = layer * (width * height);
+    let end = start + (width * height);
+    &image[start..end]
+}
+
+fn count_pixels<F>(image: &[u8], predicate: F) -> usize 
+    where F: Fn(u8) -> bool { 
+    image.iter().filter(|x| predicate(**x)).count()
+}
+
+fn layer_with_fewest_zeros(image: &[u8], width: usize, height: usize) -> Option<usize> {
+    let layers = image.len() / (width * height);
+    let min_zeros = (0..layers)
+        .map(|layer| (layer, count_pixels(pixels(image, width, height, layer), |pixel| pixel == 0)))
+        .min_by(|a, b| a.1.cmp(&b.1))?;
+    Some(min_zeros.0)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use super::*;
+
+    #[test]
+    fn decodes_layered_image() {
+        let data = Day8::parse("0222112222120000").unwrap();
+        let image = decode(&data, 2, 2);
+        assert_eq!(vec![false, true, true, false], image.pixels);
+    }
+
+    #[test]
+    fn finds_layer_with_fewest_zeros() {
+        assert_eq!(Some(2), layer_with_fewest_zeros(&[0, 0, 0, 1, 1, 1], 1, 2));
+        assert_eq!(Some(0), layer_with_fewest_zeros(&[1, 2], 1, 2));
+    }
+
+}
@@ -1,32 +1,36 @@
-use crate::intcode::{Intcode, IntcodeBuilder};
+use crate::intcode::IntcodeBuilder;
+use crate::error::{Context, Result};
+use crate::parse;
+use crate::solver::{Answer, Solver};
 
-pub fn solve() {
-    let program = include_str!("./inputs/9.txt");
-    let memory = Intcode::compile(program);
+pub struct Day9;
 
-    let mut vm = IntcodeBuilder::new()
-        .with_memory_size(4096)
-        .with_memory(&memory)
-        .with_inputs(&[1])
-        .build();
+impl Solver for Day9 {
+    type Input = Vec<i64>;
 
-    vm.run();
+    fn parse(input: &str) -> Result<Vec<i64>> {
+        Ok(parse::comma_list(input)?)
+    }
 
-    let code = vm.outputs.pop().unwrap();
+    fn part1(memory: &Vec<i64>) -> Result<Answer> {
+        Ok(boost(memory, 1)?.into())
+    }
 
-    println!("{}", code);
+    fn part2(memory: &Vec<i64>) -> Result<Answer> {
+        Ok(boost(memory, 2)?.into())
+    }
+}
 
+fn boost(memory: &[i64], mode: i64) -> Result<i64> {
     let mut vm = IntcodeBuilder::new()
         .with_memory_size(4096)
-        .with_memory(&memory)
-        .with_inputs(&[2])
+        .with_memory(memory)
+        .with_inputs(&[mode])
         .build();
 
     vm.run();
 
-    let code = vm.outputs.pop().unwrap();
-
-    println!("{}", code);
+    vm.outputs.pop().with_context(|| format!("the BOOST program produced no output in mode {}", mode))
 }
 
 #[cfg(test)]